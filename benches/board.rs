@@ -0,0 +1,96 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use tic_tac_toe::game::board::{Board, BoardConfig};
+use tic_tac_toe::game::board::bitboard::BitBoard;
+use tic_tac_toe::game::player::{Player, PlayerBuilder};
+use tic_tac_toe::game::player::controllers::random::RandomController;
+use tic_tac_toe::game::Move;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::sync::Arc;
+
+fn players() -> [Arc<Player>; 2] {
+    let builder = PlayerBuilder::new();
+    [
+        Arc::new(builder.new_player('x', RandomController::new().into()).unwrap()),
+        Arc::new(builder.new_player('o', RandomController::new().into()).unwrap()),
+    ]
+}
+
+/// A fixed, shuffled order to fill every cell of the board in
+fn move_order(config: &BoardConfig) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..config.size()).collect();
+    order.shuffle(&mut StdRng::seed_from_u64(7));
+    order
+}
+
+fn playout(c: &mut Criterion) {
+    let players = players();
+    let mut group = c.benchmark_group("playout");
+
+    for &(size, win_length) in &[(3, 3), (7, 4), (11, 5)] {
+        let config = BoardConfig::square(size, win_length).unwrap();
+        let order = move_order(&config);
+        let label = format!("{}x{}", size, size);
+
+        let empty = Board::with_config(config);
+        group.bench_with_input(BenchmarkId::new("board", &label), &order, |b, order| {
+            b.iter(|| {
+                let mut board = empty.clone();
+                for (turn, &index) in order.iter().enumerate() {
+                    let (x, y) = config.position_of(index);
+                    let next_move = Move::new(x, y, &players[turn % 2]);
+                    if board.make_move(next_move).unwrap().is_some() {
+                        break;
+                    }
+                }
+                black_box(board)
+            })
+        });
+
+        let empty = BitBoard::new(config).unwrap();
+        group.bench_with_input(BenchmarkId::new("bitboard", &label), &order, |b, order| {
+            b.iter(|| {
+                let mut bitboard = empty.clone();
+                for &index in order {
+                    if bitboard.play(index) {
+                        break;
+                    }
+                }
+                black_box(bitboard)
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn open_positions(c: &mut Criterion) {
+    let players = players();
+    let config = BoardConfig::square(7, 4).unwrap();
+    let order = move_order(&config);
+
+    // Fill half of the board without caring about wins
+    let mut board = Board::with_config(config);
+    let mut bitboard = BitBoard::new(config).unwrap();
+    for (turn, &index) in order.iter().take(config.size() / 2).enumerate() {
+        let (x, y) = config.position_of(index);
+        board.make_move(Move::new(x, y, &players[turn % 2])).unwrap();
+        bitboard.play(index);
+    }
+
+    let mut group = c.benchmark_group("open_positions");
+    group.bench_function("board", |b| b.iter(|| black_box(board.get_open_positions())));
+    group.bench_function("bitboard", |b| b.iter(|| black_box(bitboard.open_cells().count())));
+    group.finish();
+
+    let mut group = c.benchmark_group("winner_scan");
+    group.bench_function("board", |b| b.iter(|| black_box(board.check_winner().is_some())));
+    group.bench_function("bitboard", |b| b.iter(|| black_box(bitboard.winner())));
+    group.finish();
+}
+
+criterion_group!(benches, playout, open_positions);
+criterion_main!(benches);
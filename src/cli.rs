@@ -0,0 +1,970 @@
+use tic_tac_toe::game::batch::run_batch;
+use tic_tac_toe::game::board::{Board, BoardConfig};
+use tic_tac_toe::game::board::position::Position;
+use tic_tac_toe::game::cycle::{GameCycle, GameOutcome};
+use tic_tac_toe::game::profile::{Profile, Profiles};
+use tic_tac_toe::game::rating::Ratings;
+use tic_tac_toe::game::series::Match;
+use tic_tac_toe::game::tournament::{Entrant, Format, Tournament};
+use tic_tac_toe::game::record::{cell_name, GameRecord, RecordResult, RecordedTeam};
+use tic_tac_toe::game::solver::{self, Value};
+use tic_tac_toe::game::Move;
+use tic_tac_toe::game::player::{Player, PlayerBuilder};
+use tic_tac_toe::game::player::controllers::{ControllerKind, HumanController};
+use tic_tac_toe::game::player::controllers::minimax::{MinimaxController, WIN_SCORE};
+use tic_tac_toe::game::render::TerminalGuard;
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use std::error::Error;
+use std::sync::Arc;
+
+pub type CliResult = Result<(), Box<dyn Error>>;
+
+/// Where ratings are kept unless another file is given
+const RATINGS_FILE: &str = "ratings.txt";
+/// Where profiles are kept unless another file is given
+const PROFILES_FILE: &str = "profiles.txt";
+
+pub fn app() -> App<'static, 'static> {
+    let app = App::new("tic_tac_toe")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Tic-tac-toe, and any other game of getting k marks in a row on an n by m board")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("play")
+                .about("Plays a single game")
+                .args(&seat_args())
+                .arg(
+                    Arg::with_name("extra-player")
+                        .long("extra-player")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(2)
+                        .value_names(&["CONTROLLER", "SYMBOL"])
+                        .help("Adds another player, who takes their turn after the players before them. \
+                            Can be given more than once, such as for three players on a 6x6 board with -s 6 -k 4. \
+                            Only human and random players can play games between more than two sides.")
+                )
+                .arg(
+                    Arg::with_name("team")
+                        .long("team")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .value_name("[NAME=]SYMBOLS")
+                        .help("Puts the players with these symbols on a team, such as --team red=x+ --team blue=o#. \
+                            Lines of a team's marks win for the whole team, and turns alternate between teams. \
+                            [default: the teams of a resumed game]")
+                )
+                .arg(
+                    Arg::with_name("first")
+                        .long("first")
+                        .takes_value(true)
+                        .default_value("random")
+                        .help("Which player moves first: a player number counting the extra players after player 2, \
+                            or random")
+                )
+                .arg(
+                    Arg::with_name("save")
+                        .long("save")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("Writes the game to a file when it ends or is quit")
+                )
+                .arg(
+                    Arg::with_name("resume")
+                        .long("resume")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .conflicts_with_all(&["first", "size", "width", "height", "win-length"])
+                        .help("Continues a saved game, matching the players to it by their symbols")
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .takes_value(true)
+                        .help("Seeds every random choice, so games between computer players can be repeated \
+                            [default: the seed of a resumed game, or a random one]")
+                )
+                .args(&rating_args())
+                .args(&board_args())
+                .args(&dump_args())
+        )
+        .subcommand(
+            SubCommand::with_name("match")
+                .about("Plays a best-of-N match, ending early once a player can no longer be caught")
+                .args(&seat_args())
+                .arg(
+                    Arg::with_name("best-of")
+                        .long("best-of")
+                        .short("n")
+                        .takes_value(true)
+                        .default_value("5")
+                        .help("The most games the match can last")
+                )
+                .arg(
+                    Arg::with_name("first")
+                        .long("first")
+                        .takes_value(true)
+                        .possible_values(&["1", "2", "random", "alternate", "loser"])
+                        .default_value("alternate")
+                        .help("Which player moves first in every game")
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .takes_value(true)
+                        .help("Seeds every random choice, so matches between computer players can be repeated")
+                )
+                .args(&rating_args())
+                .args(&board_args())
+        )
+        .subcommand(
+            SubCommand::with_name("replay")
+                .about("Shows a saved game move by move")
+                .arg(
+                    Arg::with_name("FILE")
+                        .required(true)
+                        .help("The game record to replay")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("solve")
+                .about("Solves the game by evaluating every reachable position, which is only feasible on boards of up to 12 cells")
+                .arg(
+                    Arg::with_name("depth")
+                        .long("depth")
+                        .takes_value(true)
+                        .help("Estimates the opening moves with a minimax search this many moves deep instead")
+                )
+                .args(&board_args())
+        )
+        .subcommand(
+            SubCommand::with_name("head-to-head")
+                .alias("tournament")
+                .about("Plays many games between two computer players, alternating who moves first, and counts the wins. \
+                    Use league for more than two players.")
+                .args(&seat_args())
+                .arg(
+                    Arg::with_name("games")
+                        .long("games")
+                        .short("n")
+                        .takes_value(true)
+                        .default_value("100")
+                        .help("How many games to play")
+                )
+                .arg(
+                    Arg::with_name("threads")
+                        .long("threads")
+                        .short("j")
+                        .takes_value(true)
+                        .default_value("1")
+                        .help("How many games to play at the same time")
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .takes_value(true)
+                        .help("Seeds every random choice, so the same results are reached again")
+                )
+                .args(&board_args())
+        )
+        .subcommand(
+            SubCommand::with_name("leaderboard")
+                .about("Shows the ratings of every named player")
+                .arg(ratings_arg())
+        )
+        .subcommand(
+            SubCommand::with_name("profile")
+                .about("Creates, lists and deletes the profiles players can play under")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("create")
+                        .about("Creates a profile")
+                        .arg(
+                            Arg::with_name("NAME")
+                                .required(true)
+                                .help("The name the player is rated under, which can't be changed")
+                        )
+                        .arg(
+                            Arg::with_name("display-name")
+                                .long("display-name")
+                                .takes_value(true)
+                                .help("The name shown during games [default: NAME]")
+                        )
+                        .arg(
+                            Arg::with_name("symbol")
+                                .long("symbol")
+                                .takes_value(true)
+                                .default_value("x")
+                                .help("The mark the player places on the board unless told otherwise")
+                        )
+                        .arg(
+                            Arg::with_name("color")
+                                .long("color")
+                                .takes_value(true)
+                                .possible_values(&["red", "green", "yellow", "blue", "magenta", "cyan"])
+                                .help("The color the player's marks are drawn in")
+                        )
+                        .arg(profiles_arg())
+                )
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("Lists every profile with its results and rating")
+                        .arg(profiles_arg())
+                        .arg(ratings_arg())
+                )
+                .subcommand(
+                    SubCommand::with_name("delete")
+                        .about("Deletes a profile along with its results and rating")
+                        .arg(
+                            Arg::with_name("NAME")
+                                .required(true)
+                                .help("The profile to delete")
+                        )
+                        .arg(profiles_arg())
+                        .arg(ratings_arg())
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("league")
+                .about("Plays a round-robin or Swiss league between computer players and prints the standings")
+                .arg(
+                    Arg::with_name("ENTRANTS")
+                        .required(true)
+                        .multiple(true)
+                        .min_values(2)
+                        .help("The players taking part: random[:seed], minimax[:depth], mcts[:iterations], \
+                            or beginner, casual, hard or perfect with an optional [:blunder percent]")
+                )
+                .arg(
+                    Arg::with_name("swiss")
+                        .long("swiss")
+                        .help("Plays Swiss rounds between players with similar scores instead of every pairing")
+                )
+                .arg(
+                    Arg::with_name("rounds")
+                        .long("rounds")
+                        .takes_value(true)
+                        .requires("swiss")
+                        .help("How many Swiss rounds to play [default: enough to separate the players]")
+                )
+                .arg(
+                    Arg::with_name("games")
+                        .long("games")
+                        .short("n")
+                        .takes_value(true)
+                        .default_value("1")
+                        .help("How many games each player of a pairing moves first in")
+                )
+                .arg(
+                    Arg::with_name("threads")
+                        .long("threads")
+                        .short("j")
+                        .takes_value(true)
+                        .default_value("1")
+                        .help("How many games to play at the same time")
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .takes_value(true)
+                        .help("Seeds every random choice, so the same standings are reached again")
+                )
+                .args(&board_args())
+        );
+
+    #[cfg(feature = "serde")]
+    let app = app.subcommand(
+        SubCommand::with_name("dump")
+            .about("Prints a saved game's board and outcome as JSON")
+            .arg(
+                Arg::with_name("FILE")
+                    .required(true)
+                    .help("The game record to dump")
+            )
+    );
+
+    app
+}
+
+pub fn run(matches: &ArgMatches) -> CliResult {
+    match matches.subcommand() {
+        ("play", Some(matches)) => play(matches),
+        ("match", Some(matches)) => play_match(matches),
+        ("replay", Some(matches)) => replay(matches),
+        #[cfg(feature = "serde")]
+        ("dump", Some(matches)) => dump(matches),
+        ("solve", Some(matches)) => solve(matches),
+        ("head-to-head", Some(matches)) => head_to_head(matches),
+        ("league", Some(matches)) => league(matches),
+        ("leaderboard", Some(matches)) => leaderboard(matches),
+        ("profile", Some(matches)) => profile(matches),
+        _ => unreachable!("A subcommand is required")
+    }
+}
+
+fn seat_args() -> Vec<Arg<'static, 'static>> {
+    let controller_help = "human, random[:seed], minimax[:depth], mcts[:iterations], \
+        or beginner, casual, hard or perfect with an optional [:blunder percent]";
+    vec![
+        Arg::with_name("player1")
+            .long("player1")
+            .takes_value(true)
+            .default_value("human")
+            .help(controller_help),
+        Arg::with_name("player2")
+            .long("player2")
+            .takes_value(true)
+            .default_value("human")
+            .help(controller_help),
+        Arg::with_name("symbol1")
+            .long("symbol1")
+            .takes_value(true)
+            .default_value("x")
+            .help("The mark player 1 places on the board"),
+        Arg::with_name("symbol2")
+            .long("symbol2")
+            .takes_value(true)
+            .default_value("o")
+            .help("The mark player 2 places on the board"),
+    ]
+}
+
+/// The names or profiles players are rated under, and where the ratings and profiles are kept
+fn rating_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("name1")
+            .long("name1")
+            .takes_value(true)
+            .help("The name player 1 is rated under. Only two-player games where both players are named are rated, \
+                but a named player's wins, draws and losses against an unnamed one are still counted."),
+        Arg::with_name("name2")
+            .long("name2")
+            .takes_value(true)
+            .help("The name player 2 is rated under"),
+        Arg::with_name("profile1")
+            .long("profile1")
+            .takes_value(true)
+            .conflicts_with("name1")
+            .help("Plays player 1 under a profile, with its name, color and symbol unless --symbol1 is given"),
+        Arg::with_name("profile2")
+            .long("profile2")
+            .takes_value(true)
+            .conflicts_with("name2")
+            .help("Plays player 2 under a profile, with its name, color and symbol unless --symbol2 is given"),
+        ratings_arg(),
+        profiles_arg(),
+    ]
+}
+
+fn profiles_arg() -> Arg<'static, 'static> {
+    Arg::with_name("profiles")
+        .long("profiles")
+        .takes_value(true)
+        .value_name("FILE")
+        .default_value(PROFILES_FILE)
+        .help("The file the profiles are kept in")
+}
+
+fn ratings_arg() -> Arg<'static, 'static> {
+    Arg::with_name("ratings")
+        .long("ratings")
+        .takes_value(true)
+        .value_name("FILE")
+        .default_value(RATINGS_FILE)
+        .help("The file the ratings are kept in")
+}
+
+/// Arguments for printing the game as JSON, which are only there when serialization is enabled
+fn dump_args() -> Vec<Arg<'static, 'static>> {
+    if cfg!(feature = "serde") {
+        vec![
+            Arg::with_name("dump")
+                .long("dump")
+                .help("Prints the board and outcome as JSON when the game ends, like the dump command")
+        ]
+    } else {
+        vec![]
+    }
+}
+
+fn board_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("size")
+            .long("size")
+            .short("s")
+            .takes_value(true)
+            .help("The width and height of the board [default: 3]"),
+        Arg::with_name("width")
+            .long("width")
+            .takes_value(true)
+            .help("The width of the board, overriding --size"),
+        Arg::with_name("height")
+            .long("height")
+            .takes_value(true)
+            .help("The height of the board, overriding --size"),
+        Arg::with_name("win-length")
+            .long("win-length")
+            .short("k")
+            .takes_value(true)
+            .help("How many marks in a row win [default: the length of the board, up to 5]"),
+    ]
+}
+
+fn parse_arg<T>(matches: &ArgMatches, name: &str) -> Result<Option<T>, Box<dyn Error>>
+    where T: std::str::FromStr,
+          T::Err: Error + 'static
+{
+    match matches.value_of(name) {
+        Some(value) => value.parse()
+            .map(Some)
+            .map_err(|e: T::Err| format!("Invalid value \"{}\" for --{}: {}", value, name, e).into()),
+        None => Ok(None)
+    }
+}
+
+fn board_config(matches: &ArgMatches) -> Result<BoardConfig, Box<dyn Error>> {
+    let size: u8 = parse_arg(matches, "size")?.unwrap_or(3);
+    let width = parse_arg(matches, "width")?.unwrap_or(size);
+    let height = parse_arg(matches, "height")?.unwrap_or(size);
+    let win_length = parse_arg(matches, "win-length")?.unwrap_or_else(|| width.max(height).min(5));
+
+    Ok(BoardConfig::new(width, height, win_length)?)
+}
+
+struct Seat {
+    kind: ControllerKind,
+    symbol: char,
+    /// The name the player is rated under
+    name: Option<String>,
+    profile: Option<Profile>,
+}
+
+fn seats(matches: &ArgMatches) -> Result<[Seat; 2], Box<dyn Error>> {
+    let profiles = if matches.is_present("profile1") || matches.is_present("profile2") {
+        Profiles::load(matches.value_of("profiles").expect("The profiles file has a default"))?
+    } else {
+        Profiles::new()
+    };
+
+    let seat = |controller: &str, symbol_arg: &str, name: &str, profile: &str| -> Result<Seat, Box<dyn Error>> {
+        let kind = parse_arg(matches, controller)?.expect("Controllers have a default");
+        let symbol = matches.value_of(symbol_arg).expect("Symbols have a default");
+        let mut chars = symbol.chars();
+        let symbol = match (chars.next(), chars.next()) {
+            (Some(symbol), None) => symbol,
+            _ => return Err(format!("Symbols must be a single character, got \"{}\"", symbol).into())
+        };
+
+        match matches.value_of(profile) {
+            Some(profile) => {
+                let mut profile = profiles.get(profile)?.clone();
+                if matches.occurrences_of(symbol_arg) > 0 {
+                    profile.symbol = symbol;
+                }
+                Ok(Seat { kind, symbol: profile.symbol, name: Some(profile.name.clone()), profile: Some(profile) })
+            },
+            None => Ok(Seat { kind, symbol, name: matches.value_of(name).map(str::to_string), profile: None })
+        }
+    };
+
+    let seats = [
+        seat("player1", "symbol1", "name1", "profile1")?,
+        seat("player2", "symbol2", "name2", "profile2")?,
+    ];
+    if seats[0].symbol == seats[1].symbol {
+        return Err(format!(
+            "Both players would play with '{}', choose another symbol with --symbol1 or --symbol2", seats[0].symbol
+        ).into());
+    }
+    if seats[0].name.is_some() && seats[0].name == seats[1].name {
+        return Err("The players need different names".into());
+    }
+    Ok(seats)
+}
+
+/// The players added with `--extra-player`, in the order they were given
+fn extra_seats(matches: &ArgMatches) -> Result<Vec<Seat>, Box<dyn Error>> {
+    let values: Vec<&str> = matches.values_of("extra-player").map_or_else(Vec::new, Iterator::collect);
+    values.chunks(2)
+        .map(|pair| {
+            let kind = pair[0].parse()
+                .map_err(|e| format!("Invalid value \"{}\" for --extra-player: {}", pair[0], e))?;
+            let mut chars = pair[1].chars();
+            match (chars.next(), chars.next()) {
+                (Some(symbol), None) => Ok(Seat { kind, symbol, name: None, profile: None }),
+                _ => Err(format!("Symbols must be a single character, got \"{}\"", pair[1]).into())
+            }
+        })
+        .collect()
+}
+
+fn player(builder: &PlayerBuilder, seat: &Seat) -> Result<Player, Box<dyn Error>> {
+    let controller = seat.kind.build_seeded(builder.next_seed());
+    let player = match (&seat.profile, &seat.name) {
+        (Some(profile), _) => builder.new_profile_player(profile, controller),
+        (None, Some(name)) => builder.new_named_player(name, seat.symbol, controller),
+        (None, None) => builder.new_player(seat.symbol, controller)
+    };
+    Ok(player?)
+}
+
+fn players(builder: &PlayerBuilder, [seat1, seat2]: &[Seat; 2]) -> Result<(Player, Player), Box<dyn Error>> {
+    Ok((player(builder, seat1)?, player(builder, seat2)?))
+}
+
+/// Puts players on the teams given as `[NAME=]SYMBOLS`, where teams without a name are named
+/// after their symbols
+fn assign_teams(builder: &PlayerBuilder, players: &mut [Player], teams: &[&str]) -> CliResult {
+    for &team in teams {
+        let (name, symbols) = team.split_once('=').unwrap_or((team, team));
+        if !RecordedTeam::is_valid_name(name) {
+            return Err(format!("\"{}\" can't be used as a team name, names can't be empty or contain spaces or =", name).into());
+        }
+        if symbols.is_empty() {
+            return Err(format!("The team \"{}\" has no players", name).into());
+        }
+        let team = builder.new_team(name)?;
+        for symbol in symbols.chars() {
+            let player = players.iter_mut()
+                .find(|player| *player.get_symbol() == symbol)
+                .ok_or_else(|| format!("No player uses the symbol '{}'", symbol))?;
+            if player.team().is_some() {
+                return Err(format!("The player with the symbol '{}' is on more than one team", symbol).into());
+            }
+            builder.assign_team(player, &team);
+        }
+    }
+    Ok(())
+}
+
+/// Loads the ratings for games with named players, so a bad ratings file or name is reported
+/// before any game is played. Games without a named player aren't recorded.
+fn load_ratings(matches: &ArgMatches, player1: &Player, player2: &Player) -> Result<Option<Ratings>, Box<dyn Error>> {
+    let names: Vec<&str> = player1.name().into_iter().chain(player2.name()).collect();
+    if names.is_empty() {
+        return Ok(None);
+    }
+    for name in names {
+        Ratings::check_name(name)?;
+    }
+
+    let path = matches.value_of("ratings").expect("The ratings file has a default");
+    Ok(Some(Ratings::load(path)?))
+}
+
+/// Records finished games for the named players, saving the ratings afterwards
+fn rate_games<'a, I>(matches: &ArgMatches, mut ratings: Ratings, player1: &Player, player2: &Player, outcomes: I) -> CliResult
+    where I: IntoIterator<Item = &'a GameOutcome>
+{
+    let mut rated = 0;
+    for outcome in outcomes {
+        if ratings.record_outcome(player1, player2, outcome)? {
+            rated += 1;
+        }
+    }
+    if rated == 0 {
+        return Ok(());
+    }
+    ratings.save(matches.value_of("ratings").expect("The ratings file has a default"))?;
+
+    for (player, name) in [player1, player2].iter().filter_map(|player| Some((player, player.name()?))) {
+        let rating = ratings.get(name).expect("Recorded players have a rating");
+        println!("{} is now rated {:.0} (Glicko-2 {:.0} ± {:.0})", player, rating.elo, rating.glicko.rating, 2.0 * rating.glicko.deviation);
+    }
+    Ok(())
+}
+
+fn play(matches: &ArgMatches) -> CliResult {
+    let seats = seats(matches)?;
+    let extra_seats = extra_seats(matches)?;
+    let resumed = matches.value_of("resume").map(GameRecord::load).transpose()?;
+    let seed = match parse_arg(matches, "seed")? {
+        Some(seed) => seed,
+        None => resumed.as_ref().and_then(GameRecord::seed).unwrap_or_else(rand::random)
+    };
+    let builder = PlayerBuilder::with_seed(seed);
+    let (player1, player2) = players(&builder, &seats)?;
+    let mut all_players = vec![player1, player2];
+    for seat in &extra_seats {
+        all_players.push(player(&builder, seat)?);
+    }
+
+    let teams = matches.values_of("team").map_or_else(Vec::new, Iterator::collect);
+    assign_teams(&builder, &mut all_players, &teams)?;
+    if teams.is_empty() {
+        if let Some(record) = &resumed {
+            record.assign_teams(&builder, &mut all_players)?;
+        }
+    }
+
+    let mut cycle = match &resumed {
+        Some(record) => GameCycle::resume_players(all_players, record)?,
+        None if teams.is_empty() => GameCycle::with_players(all_players, board_config(matches)?)?,
+        None => GameCycle::with_teams(all_players, board_config(matches)?)?
+    };
+    cycle.set_seed(seed);
+    cycle.set_starting_rule(parse_arg(matches, "first")?.expect("First has a default"))?;
+    let ratings = match cycle.players() {
+        [player1, player2] => load_ratings(matches, player1, player2)?,
+        _ => None
+    };
+    let outcome = {
+        // Human players need the full-screen board to pick their moves
+        let _terminal = if seats.iter().chain(&extra_seats).any(|seat| seat.kind.is_human()) {
+            Some(TerminalGuard::new()?)
+        } else {
+            None
+        };
+
+        if resumed.is_some() {
+            cycle.cycle_resumed()
+        } else {
+            cycle.cycle()
+        }
+    };
+
+    println!("{}", cycle.board());
+    println!("{}", outcome);
+    println!("{} moves were played", cycle.board().history().len());
+    #[cfg(feature = "serde")]
+    {
+        if matches.is_present("dump") {
+            print_json(cycle.board(), Some(&outcome))?;
+        }
+    }
+    if let Some(path) = matches.value_of("save") {
+        cycle.record(Some(&outcome)).save(path)?;
+        println!("The game was saved to {}", path);
+    }
+    if let Some(ratings) = ratings {
+        rate_games(matches, ratings, cycle.player1(), cycle.player2(), Some(&outcome))?;
+    }
+    Ok(())
+}
+
+fn play_match(matches: &ArgMatches) -> CliResult {
+    let seats = seats(matches)?;
+    let best_of = parse_arg(matches, "best-of")?.expect("Best of has a default");
+    let seed = parse_arg(matches, "seed")?.unwrap_or_else(rand::random);
+    let (player1, player2) = players(&PlayerBuilder::with_seed(seed), &seats)?;
+
+    let ratings = load_ratings(matches, &player1, &player2)?;
+
+    let mut cycle = GameCycle::with_config(player1, player2, board_config(matches)?);
+    cycle.set_seed(seed);
+    let mut series = Match::new(cycle, best_of);
+    series.cycle_mut().set_starting_rule(parse_arg(matches, "first")?.expect("First has a default"))?;
+
+    let summary = {
+        let _terminal = if seats.iter().any(|seat| seat.kind.is_human()) {
+            Some(TerminalGuard::new()?)
+        } else {
+            None
+        };
+        series.play()
+    };
+
+    for (number, game) in series.games().iter().enumerate() {
+        println!("Game {}, started by {}: {}", number + 1, game.starter, game.outcome);
+    }
+    println!("{}", summary);
+    let outcomes = series.games().iter().map(|game| &game.outcome);
+    if let Some(ratings) = ratings {
+        rate_games(matches, ratings, &summary.player1, &summary.player2, outcomes)?;
+    }
+    Ok(())
+}
+
+/// Players for every symbol of a record. They only place the recorded moves, so they never need
+/// to be asked for one.
+fn record_players(record: &GameRecord) -> Result<Vec<Arc<Player>>, Box<dyn Error>> {
+    let builder = PlayerBuilder::new();
+    let mut players = record.symbols().iter()
+        .map(|&symbol| builder.new_player(symbol, HumanController.into()))
+        .collect::<Result<Vec<Player>, _>>()?;
+    record.assign_teams(&builder, &mut players)?;
+    Ok(players.into_iter().map(Arc::new).collect())
+}
+
+fn replay(matches: &ArgMatches) -> CliResult {
+    let record = GameRecord::load(matches.value_of("FILE").expect("The file is required"))?;
+    let players = record_players(&record)?;
+
+    let mut board = Board::with_config(*record.config());
+    for (index, recorded) in record.moves().iter().enumerate() {
+        let player = players.iter()
+            .find(|player| *player.get_symbol() == recorded.symbol)
+            .expect("Loaded records only contain moves by their players");
+        board.make_move(Move::new(recorded.x_pos, recorded.y_pos, player))?;
+        println!("{}. {} plays {}", index + 1, player, cell_name(recorded.x_pos, recorded.y_pos));
+        println!("{}", board);
+    }
+
+    match record.result() {
+        RecordResult::Win(symbol) => {
+            let winner = players.iter()
+                .find(|player| *player.get_symbol() == symbol)
+                .expect("Loaded records are won by one of their players");
+            match winner.team() {
+                Some(team) => {
+                    let members: Vec<String> = players.iter()
+                        .filter(|player| player.is_teammate(winner))
+                        .map(ToString::to_string)
+                        .collect();
+                    println!("{} ({}) won", team, members.join(", "));
+                },
+                None => println!("Player {} won", symbol)
+            }
+        },
+        RecordResult::Draw => println!("The game was a draw"),
+        RecordResult::Unfinished => println!("The game is unfinished")
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+fn dump(matches: &ArgMatches) -> CliResult {
+    let record = GameRecord::load(matches.value_of("FILE").expect("The file is required"))?;
+    let players = record_players(&record)?;
+    let board = record.replay(&players)?;
+
+    // Unfinished games have no outcome yet
+    let outcome = match board.check_winner() {
+        Some(winner) => Some(GameOutcome::win(winner, board.history().len(), &players)),
+        None if board.is_full() => Some(GameOutcome::Draw),
+        None => None
+    };
+
+    print_json(&board, outcome.as_ref())
+}
+
+#[cfg(feature = "serde")]
+fn print_json(board: &Board, outcome: Option<&GameOutcome>) -> CliResult {
+    let json = serde_json::json!({ "board": board, "outcome": outcome });
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+fn solve(matches: &ArgMatches) -> CliResult {
+    let config = board_config(matches)?;
+    let depth = parse_arg(matches, "depth")?;
+
+    // Without a depth limit, every reachable position is solved exactly
+    let (values, best) = match depth {
+        Some(depth) => {
+            let mut position = Position::new(config);
+            let controller = MinimaxController::with_max_depth(depth);
+            let (mut scores, stats) = controller.evaluate_moves_with_stats(&mut position);
+            scores.sort_by_key(|&((x, y), _)| config.index_of(x, y));
+
+            let capacity = controller.table().lock().expect("The search has finished").capacity();
+            println!(
+                "Transposition table: {} lookups, {} hits ({:.1}%), {} replacements in {} slots",
+                stats.probes, stats.hits, stats.hit_rate() * 100.0, stats.replacements, capacity
+            );
+            println!();
+
+            let values: Vec<Value> = scores.iter().map(|&(_, score)| score_value(score)).collect();
+            let best = values.iter().max().copied();
+            (values, best)
+        },
+        None => {
+            let evaluation = solver::solve(&Board::with_config(config))?;
+            let stats = evaluation.stats();
+            println!("Reachable positions: {}", stats.positions);
+            println!(
+                "Finished positions: {} ({} won by the first player, {} by the second, {} drawn)",
+                stats.terminal_positions(), stats.wins[0], stats.wins[1], stats.draws
+            );
+            println!();
+
+            let values = evaluation.move_values().into_iter().map(|(_, value)| value).collect();
+            (values, Some(evaluation.value()))
+        }
+    };
+
+    println!("Value of every opening move for the first player:");
+    for row in values.chunks(config.width() as usize) {
+        for value in row {
+            print!("{:>5}", value.to_string());
+        }
+        println!();
+    }
+    println!("(+n: wins within n moves, -n: loses within n moves, =: draw)");
+
+    if let Some(best) = best {
+        let value = match best {
+            Value::Win(_) => "a win for the first player",
+            Value::Loss(_) => "a win for the second player",
+            Value::Draw if depth.is_some() => "a draw as far as the search can see",
+            Value::Draw => "a draw"
+        };
+        println!("With perfect play the game is {}", value);
+    }
+    Ok(())
+}
+
+/// Converts a minimax score into a value, counting the move that is scored
+fn score_value(score: i32) -> Value {
+    if score > 0 {
+        Value::Win((WIN_SCORE - score + 1) as u8)
+    } else if score < 0 {
+        Value::Loss((WIN_SCORE + score + 1) as u8)
+    } else {
+        Value::Draw
+    }
+}
+
+fn head_to_head(matches: &ArgMatches) -> CliResult {
+    let config = board_config(matches)?;
+    let seats = seats(matches)?;
+    if seats.iter().any(|seat| seat.kind.is_human()) {
+        return Err("Head-to-head games can only be played between computer players".into());
+    }
+    let games: u32 = parse_arg(matches, "games")?.expect("Games has a default");
+    let threads: usize = parse_arg(matches, "threads")?.expect("Threads has a default");
+    let seed: Option<u64> = parse_arg(matches, "seed")?;
+    // Every game gets its own seed, so the results don't depend on which thread plays it
+    let builder = |game: u32| match seed {
+        Some(seed) => PlayerBuilder::with_seed(seed.wrapping_add(game as u64)),
+        None => PlayerBuilder::new()
+    };
+
+    // Make sure the players can be created before starting any threads
+    players(&builder(0), &seats)?;
+    let results = run_batch(games, threads, |game| {
+        let (player1, player2) = players(&builder(game), &seats).expect("Players were checked to be valid");
+        GameCycle::with_config(player1, player2, config)
+    });
+    let wins = [results.player1_wins, results.player2_wins];
+    let (draws, aborted) = (results.draws, results.aborted);
+
+    println!("{:<20} {:>6}", "Player", "Wins");
+    for (seat, wins) in seats.iter().zip(&wins) {
+        println!("{:<20} {:>6}", format!("{} ({})", seat.symbol, seat.kind), wins);
+    }
+    println!("{:<20} {:>6}", "Draws", draws);
+    if aborted > 0 {
+        println!("{:<20} {:>6}", "Aborted", aborted);
+    }
+    Ok(())
+}
+
+fn league(matches: &ArgMatches) -> CliResult {
+    let entrants = matches.values_of("ENTRANTS").expect("Entrants are required")
+        .map(|kind| match kind.parse::<ControllerKind>() {
+            Ok(kind) if kind.is_human() => Err("Leagues can only be played between computer players".into()),
+            Ok(kind) => Ok(Entrant::new(kind)),
+            Err(e) => Err(Box::new(e) as Box<dyn Error>)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let format = match (matches.is_present("swiss"), parse_arg(matches, "rounds")?) {
+        (true, Some(rounds)) => Format::Swiss { rounds },
+        (true, None) => Format::swiss_for(entrants.len()),
+        (false, _) => Format::RoundRobin
+    };
+    let mut tournament = Tournament::new(entrants, format)?;
+    tournament.set_config(board_config(matches)?);
+    tournament.set_games_per_side(parse_arg(matches, "games")?.expect("Games has a default"));
+    tournament.set_threads(parse_arg(matches, "threads")?.expect("Threads has a default"));
+    if let Some(seed) = parse_arg(matches, "seed")? {
+        tournament.set_seed(seed);
+    }
+
+    let standings = tournament.run();
+    println!("{} games played", standings.games());
+    println!();
+    print!("{}", standings);
+    Ok(())
+}
+
+fn leaderboard(matches: &ArgMatches) -> CliResult {
+    let ratings = Ratings::load(matches.value_of("ratings").expect("The ratings file has a default"))?;
+    let leaderboard = ratings.leaderboard();
+    if leaderboard.is_empty() {
+        println!("No games have been rated yet");
+        return Ok(());
+    }
+
+    let width = leaderboard.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0).max("Player".len());
+    println!("{:>3}  {:<width$} {:>6} {:>6} {:>5} {:>5} {:>5} {:>13}", "#", "Player", "Elo", "Games", "W", "D", "L", "Glicko-2", width = width);
+    for (rank, (name, rating)) in leaderboard.iter().enumerate() {
+        let glicko = format!("{:.0} ± {:.0}", rating.glicko.rating, 2.0 * rating.glicko.deviation);
+        println!(
+            "{:>3}  {:<width$} {:>6.0} {:>6} {:>5} {:>5} {:>5} {:>13}",
+            rank + 1, name, rating.elo, rating.games(), rating.wins, rating.draws, rating.losses, glicko, width = width
+        );
+    }
+    Ok(())
+}
+
+fn profile(matches: &ArgMatches) -> CliResult {
+    let (command, matches) = matches.subcommand();
+    let matches = matches.expect("A profile command is required");
+    let path = matches.value_of("profiles").expect("The profiles file has a default");
+    let mut profiles = Profiles::load(path)?;
+
+    match command {
+        "create" => {
+            let name = matches.value_of("NAME").expect("The name is required");
+            let symbol = matches.value_of("symbol").expect("The symbol has a default");
+            let mut chars = symbol.chars();
+            let mut profile = match (chars.next(), chars.next()) {
+                (Some(symbol), None) => Profile::new(name, symbol),
+                _ => return Err(format!("Symbols must be a single character, got \"{}\"", symbol).into())
+            };
+            if let Some(display_name) = matches.value_of("display-name") {
+                profile = profile.with_display_name(display_name);
+            }
+            if let Some(color) = parse_arg(matches, "color")? {
+                profile = profile.with_color(color);
+            }
+
+            profiles.create(profile)?;
+            profiles.save(path)?;
+            println!("Created the profile \"{}\"", name);
+        },
+        "list" => {
+            let ratings = Ratings::load(matches.value_of("ratings").expect("The ratings file has a default"))?;
+            if profiles.iter().next().is_none() {
+                println!("There are no profiles yet");
+                return Ok(());
+            }
+
+            let width = profiles.iter()
+                .map(|profile| profile.name.chars().count().max(profile.display_name.chars().count()))
+                .max()
+                .unwrap_or(0)
+                .max("Display name".len());
+            println!(
+                "{:<width$}  {:<width$}  {:<6}  {:<7}  {:>5}  {:>5}  {:>5}  {:>5}",
+                "Name", "Display name", "Symbol", "Color", "W", "D", "L", "Elo", width = width
+            );
+            for profile in profiles.iter() {
+                let rating = profile.rating(&ratings);
+                let color = profile.color.map_or_else(|| "-".to_string(), |color| color.to_string());
+                println!(
+                    "{:<width$}  {:<width$}  {:<6}  {:<7}  {:>5}  {:>5}  {:>5}  {:>5.0}",
+                    profile.name, profile.display_name, profile.symbol, color,
+                    rating.wins, rating.draws, rating.losses, rating.elo, width = width
+                );
+            }
+        },
+        "delete" => {
+            let profile = profiles.delete(matches.value_of("NAME").expect("The name is required"))?;
+            profiles.save(path)?;
+
+            let ratings_path = matches.value_of("ratings").expect("The ratings file has a default");
+            let mut ratings = Ratings::load(ratings_path)?;
+            if ratings.remove(&profile.name).is_some() {
+                ratings.save(ratings_path)?;
+            }
+            println!("Deleted the profile \"{}\"", profile.name);
+        },
+        _ => unreachable!("A profile command is required")
+    }
+    Ok(())
+}
@@ -1,13 +1,15 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Move {
     pub x_pos: u8,
     pub y_pos: u8,
-    pub player: Rc<player::Player>,
+    pub player: Arc<player::Player>,
 }
 
 impl Move {
-    pub fn new(x_pos: u8, y_pos: u8, player: &Rc<player::Player>) -> Self {
+    pub fn new(x_pos: u8, y_pos: u8, player: &Arc<player::Player>) -> Self {
         Self {
             x_pos,
             y_pos,
@@ -18,7 +20,30 @@ impl Move {
 
 }
 
+/// Something a player can do on their turn
+#[derive(Debug)]
+pub enum Action {
+    Place(Move),
+    /// Take back moves until the requesting player's last move has been taken back
+    Undo,
+    /// Replay taken back moves until it is the requesting player's turn again
+    Redo,
+    /// Leave the game, abandoning it
+    Quit,
+    /// Abandon the game because the player can't go on, for the given reason
+    Abandon(String),
+}
+
 pub mod board;
 pub mod player;
 
 pub mod cycle;
+pub mod series;
+pub mod tournament;
+pub mod batch;
+pub mod render;
+pub mod record;
+pub mod solver;
+pub mod rating;
+pub mod profile;
+mod tab_separated;
@@ -0,0 +1,142 @@
+use crate::game::cycle::{CurrentPlayer, GameCycle, GameOutcome};
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// The combined results of many games between the same two seats
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BatchResults {
+    pub player1_wins: u32,
+    pub player2_wins: u32,
+    pub draws: u32,
+    pub aborted: u32,
+}
+
+impl BatchResults {
+    pub fn games(&self) -> u32 {
+        self.player1_wins + self.player2_wins + self.draws + self.aborted
+    }
+
+    fn record(&mut self, cycle: &GameCycle, outcome: &GameOutcome) {
+        match outcome {
+            GameOutcome::Win { player, .. } if player == cycle.player1() => self.player1_wins += 1,
+            GameOutcome::Win { .. } => self.player2_wins += 1,
+            GameOutcome::Draw => self.draws += 1,
+            GameOutcome::Aborted { .. } => self.aborted += 1,
+        }
+    }
+
+    fn merge(&mut self, other: &BatchResults) {
+        self.player1_wins += other.player1_wins;
+        self.player2_wins += other.player2_wins;
+        self.draws += other.draws;
+        self.aborted += other.aborted;
+    }
+}
+
+/// Plays `games` games spread across `threads` worker threads, without displaying them.
+///
+/// `make_game` is called with the index of every game to set it up. Player 1 moves first in
+/// even numbered games and player 2 moves first in odd numbered games.
+pub fn run_batch<F>(games: u32, threads: usize, make_game: F) -> BatchResults
+    where F: Fn(u32) -> GameCycle + Sync
+{
+    let games = run_parallel(games, threads, |game| {
+        let mut cycle = make_game(game);
+        cycle.set_display(false);
+        let first = if game % 2 == 0 { CurrentPlayer::PLAYER1 } else { CurrentPlayer::PLAYER2 };
+        let outcome = cycle.cycle_from(first);
+
+        let mut result = BatchResults::default();
+        result.record(&cycle, &outcome);
+        result
+    });
+
+    let mut totals = BatchResults::default();
+    for result in &games {
+        totals.merge(result);
+    }
+    totals
+}
+
+/// Runs `task` for every index below `count`, spread across `threads` worker threads, and
+/// returns the results in the order of their indices
+pub fn run_parallel<T, F>(count: u32, threads: usize, task: F) -> Vec<T>
+    where T: Send,
+          F: Fn(u32) -> T + Sync
+{
+    let next = AtomicU32::new(0);
+    let results = Mutex::new(Vec::with_capacity(count as usize));
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                let mut finished = vec![];
+                loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    if index >= count {
+                        break;
+                    }
+                    finished.push((index, task(index)));
+                }
+
+                results.lock().unwrap().extend(finished);
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|&(index, _)| index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::board::Board;
+    use crate::game::player::PlayerBuilder;
+    use crate::game::player::controllers::minimax::MinimaxController;
+    use crate::game::player::controllers::random::RandomController;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn core_types_are_thread_safe() {
+        assert_send_sync::<Board>();
+        assert_send_sync::<GameCycle>();
+        assert_send_sync::<GameOutcome>();
+        assert_send_sync::<crate::game::Move>();
+        assert_send_sync::<crate::game::board::Winner>();
+    }
+
+    #[test]
+    fn game_runs_on_another_thread() {
+        let builder = PlayerBuilder::new();
+        let p1 = builder.new_player('x', MinimaxController::new().into()).unwrap();
+        let p2 = builder.new_player('o', MinimaxController::new().into()).unwrap();
+        let mut cycle = GameCycle::new(p1, p2);
+        cycle.set_display(false);
+
+        let outcome = thread::spawn(move || cycle.cycle()).join().unwrap();
+        match outcome {
+            GameOutcome::Draw => {},
+            outcome => panic!("Perfect players should draw, got {:?}", outcome)
+        }
+    }
+
+    #[test]
+    fn batch_plays_every_game() {
+        let results = run_batch(40, 4, |_| {
+            let builder = PlayerBuilder::new();
+            let p1 = builder.new_player('x', MinimaxController::new().into()).unwrap();
+            let p2 = builder.new_player('o', RandomController::new().into()).unwrap();
+            GameCycle::new(p1, p2)
+        });
+
+        assert_eq!(results.games(), 40);
+        assert_eq!(results.player2_wins, 0, "Random play should never beat minimax");
+        assert_eq!(results.aborted, 0);
+        assert!(results.player1_wins > 0);
+    }
+}
@@ -1,203 +1,361 @@
 use crate::game::player::Player;
 use crate::game::Move;
-use std::rc::Rc;
+use std::sync::Arc;
 use std::fmt::{Debug, Display, Formatter};
 use std::fmt::Result as FmtResult;
 
-pub struct Board([[Option<Rc<Player>>; 3]; 3]);
+pub mod position;
+pub mod bitboard;
+pub mod symmetry;
+pub mod zobrist;
+#[cfg(feature = "serde")]
+mod serde_impls;
+
+/// The dimensions of a board and how many marks in a row are needed to win
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "serde_impls::BoardConfigFields"))]
+pub struct BoardConfig {
+    width: u8,
+    height: u8,
+    win_length: u8,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BoardConfigError {
+    EmptyBoard,
+    WinLengthTooLong(u8),
+}
+
+impl Display for BoardConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            BoardConfigError::EmptyBoard => write!(f, "The board and win length must be at least 1"),
+            BoardConfigError::WinLengthTooLong(length) => {
+                write!(f, "A line of {} can't fit on the board", length)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BoardConfigError {}
+
+impl BoardConfig {
+    pub fn new(width: u8, height: u8, win_length: u8) -> Result<Self, BoardConfigError> {
+        if width == 0 || height == 0 || win_length == 0 {
+            return Err(BoardConfigError::EmptyBoard);
+        }
+
+        if win_length > width.max(height) {
+            return Err(BoardConfigError::WinLengthTooLong(win_length));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            win_length,
+        })
+    }
+
+    pub fn square(size: u8, win_length: u8) -> Result<Self, BoardConfigError> {
+        Self::new(size, size, win_length)
+    }
+
+    pub fn width(&self) -> u8 {
+        self.width
+    }
+
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    pub fn win_length(&self) -> u8 {
+        self.win_length
+    }
+
+    /// The total number of cells on the board
+    pub fn size(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    pub fn in_bounds(&self, x_pos: u8, y_pos: u8) -> bool {
+        x_pos < self.width && y_pos < self.height
+    }
+
+    /// Converts a position into an index into a row-major list of cells
+    pub fn index_of(&self, x_pos: u8, y_pos: u8) -> usize {
+        y_pos as usize * self.width as usize + x_pos as usize
+    }
+
+    pub fn position_of(&self, index: usize) -> (u8, u8) {
+        ((index % self.width as usize) as u8, (index / self.width as usize) as u8)
+    }
 
-pub struct Winner(Rc<Player>);
+    /// Finds a run of at least `win_length` positions passing through (x_pos, y_pos) for which
+    /// `owned` returns true. The full run is returned, ordered from one end to the other.
+    pub fn line_through<F>(&self, x_pos: u8, y_pos: u8, owned: F) -> Option<Vec<(u8, u8)>>
+        where F: Fn(u8, u8) -> bool
+    {
+        const DIRECTIONS: [(i16, i16); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+        if !self.in_bounds(x_pos, y_pos) || !owned(x_pos, y_pos) {
+            return None;
+        }
+
+        let step = |(x, y): (u8, u8), (dx, dy): (i16, i16)| -> Option<(u8, u8)> {
+            let next_x = x as i16 + dx;
+            let next_y = y as i16 + dy;
+            if next_x < 0 || next_y < 0 || next_x >= self.width as i16 || next_y >= self.height as i16 {
+                return None;
+            }
+
+            let next = (next_x as u8, next_y as u8);
+            if owned(next.0, next.1) {
+                Some(next)
+            } else {
+                None
+            }
+        };
+
+        for &(dx, dy) in &DIRECTIONS {
+            let mut start = (x_pos, y_pos);
+            while let Some(previous) = step(start, (-dx, -dy)) {
+                start = previous;
+            }
+
+            let mut line = vec![start];
+            let mut current = start;
+            while let Some(next) = step(current, (dx, dy)) {
+                line.push(next);
+                current = next;
+            }
+
+            if line.len() >= self.win_length as usize {
+                return Some(line);
+            }
+        }
+
+        None
+    }
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        Self {
+            width: 3,
+            height: 3,
+            win_length: 3,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "serde_impls::BoardFields"))]
+pub struct Board {
+    config: BoardConfig,
+    cells: Vec<Option<Arc<Player>>>,
+    history: Vec<Move>,
+    undone: Vec<Move>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    hash: u64,
+}
+
+/// The player that completed a line, along with the positions that make up that line
+#[derive(Debug)]
+pub struct Winner(Arc<Player>, Vec<(u8, u8)>);
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MoveError {
     OutOfBounds(u8, u8),
-    PositionAlreadyFilled(Rc<Player>),
+    PositionAlreadyFilled(Arc<Player>),
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            MoveError::OutOfBounds(x_pos, y_pos) => write!(f, "({}, {}) is outside the board", x_pos, y_pos),
+            MoveError::PositionAlreadyFilled(player) => write!(f, "The position is already taken by {}", player)
+        }
+    }
 }
 
+impl std::error::Error for MoveError {}
+
 pub type MoveResult = Result<Option<Winner>, MoveError>;
 
+impl Winner {
+    pub fn player(&self) -> &Arc<Player> {
+        &self.0
+    }
+
+    pub fn line(&self) -> &[(u8, u8)] {
+        &self.1
+    }
+
+    pub fn into_parts(self) -> (Arc<Player>, Vec<(u8, u8)>) {
+        (self.0, self.1)
+    }
+}
+
 impl Board {
     pub fn new() -> Self {
-        let base: [Option<Rc<Player>>; 3] = Default::default();
+        Self::with_config(BoardConfig::default())
+    }
 
-        Self([base.clone(), base.clone(), base.clone()])
+    pub fn with_config(config: BoardConfig) -> Self {
+        Self {
+            config,
+            cells: vec![None; config.size()],
+            history: vec![],
+            undone: vec![],
+            hash: 0,
+        }
     }
 
-    pub fn get_open_positions(&self) -> Vec<(u8, u8)> {
-        let mut output = vec![];
+    pub fn config(&self) -> &BoardConfig {
+        &self.config
+    }
 
-        for (i, row) in self.0.iter().enumerate() {
-            for (j, o) in row.iter().enumerate() {
-                if o.is_none() {
-                    output.push((i as u8, j as u8));
-                }
-            }
-        }
+    /// The Zobrist hash of the marks on the board. Boards with the same marks in the same cells
+    /// have the same hash, whatever order the moves were played in.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
 
-        output
+    fn cell_key(&self, x_pos: u8, y_pos: u8, player: &Player) -> u64 {
+        zobrist::cell_key(self.config.index_of(x_pos, y_pos), player.id() as u32 as u64)
     }
 
-    pub fn filled_positions(&self) -> u8 {
-        let mut count = 0;
-        for rows in &self.0 {
-            for o in rows {
-                if o.is_some() {
-                    count += 1;
-                }
-            }
-        }
-        count
+    /// Every move that is currently on the board, in the order they were played
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    pub fn last_move(&self) -> Option<&Move> {
+        self.history.last()
+    }
+
+    /// The move that would be replayed by [`redo`](#method.redo)
+    pub fn next_redo(&self) -> Option<&Move> {
+        self.undone.last()
+    }
+
+    pub fn get_open_positions(&self) -> Vec<(u8, u8)> {
+        self.cells.iter()
+            .enumerate()
+            .filter(|(_, o)| o.is_none())
+            .map(|(index, _)| self.config.position_of(index))
+            .collect()
     }
 
-    pub fn get_at_pos(&mut self, x_pos: u8, y_pos: u8) -> Result<&Option<Rc<Player>>, ()> {
-        if x_pos >= 3 || y_pos >= 3 {
-            return Err(());
+    pub fn filled_positions(&self) -> usize {
+        self.cells.iter().filter(|o| o.is_some()).count()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.filled_positions() == self.config.size()
+    }
+
+    pub fn get_at_pos(&self, x_pos: u8, y_pos: u8) -> Result<&Option<Arc<Player>>, MoveError> {
+        if !self.config.in_bounds(x_pos, y_pos) {
+            return Err(MoveError::OutOfBounds(x_pos, y_pos));
         }
 
-        Ok(&self.0[y_pos as usize][x_pos as usize])
+        Ok(&self.cells[self.config.index_of(x_pos, y_pos)])
     }
 
-    fn get_at_pos_mut(&mut self, x_pos: u8, y_pos: u8) -> Result<&mut Option<Rc<Player>>, ()> {
-        if x_pos >= 3 || y_pos >= 3 {
-            return Err(());
+    fn get_at_pos_mut(&mut self, x_pos: u8, y_pos: u8) -> Result<&mut Option<Arc<Player>>, MoveError> {
+        if !self.config.in_bounds(x_pos, y_pos) {
+            return Err(MoveError::OutOfBounds(x_pos, y_pos));
         }
 
-        Ok(&mut self.0[y_pos as usize][x_pos as usize])
+        let index = self.config.index_of(x_pos, y_pos);
+        Ok(&mut self.cells[index])
     }
 
+    /// Places a move on the board. Playing a new move discards any moves that could have been redone.
     pub fn make_move(&mut self, next_move: Move) -> MoveResult {
+        let result = self.place(next_move)?;
+        self.undone.clear();
+        Ok(result)
+    }
+
+    fn place(&mut self, next_move: Move) -> MoveResult {
         let Move {
             x_pos,
             y_pos,
-            player,
+            ref player,
         } = next_move;
-        if x_pos >= 3 || y_pos >= 3 {
-            return Err(MoveError::OutOfBounds(x_pos, y_pos));
-        }
 
-        let position_result = self.get_at_pos_mut(x_pos, y_pos);
-        match position_result {
-            Err(()) => Err(MoveError::OutOfBounds(x_pos, y_pos)),
-            Ok(Some(other_player)) => Err(MoveError::PositionAlreadyFilled(other_player.clone())),
-            Ok(empty_space) => {
-                *empty_space = Some(player);
-                MoveResult::Ok(self.check_winner())
+        match self.get_at_pos_mut(x_pos, y_pos)? {
+            Some(other_player) => Err(MoveError::PositionAlreadyFilled(other_player.clone())),
+            empty_space => {
+                *empty_space = Some(player.clone());
+                self.hash ^= self.cell_key(x_pos, y_pos, player);
+                let winner = self.winning_line_through(x_pos, y_pos).map(|line| Winner(player.clone(), line));
+                self.history.push(next_move);
+                Ok(winner)
             }
         }
     }
 
-    fn check_winner(&self) -> Option<Winner> {
-        if let Some(winner) = self.check_winner_row() {
-            Some(winner)
-        } else if let Some(winner) = self.check_winner_column() {
-            Some(winner)
-        } else if let Some(winner) = self.check_winner_diagonal() {
-            Some(winner)
-        } else {
-            None
-        }
+    /// Takes back the last move played, returning it
+    pub fn undo(&mut self) -> Option<Move> {
+        let last_move = self.history.pop()?;
+        let index = self.config.index_of(last_move.x_pos, last_move.y_pos);
+        self.cells[index] = None;
+        self.hash ^= self.cell_key(last_move.x_pos, last_move.y_pos, &last_move.player);
+        self.undone.push(last_move.clone());
+        Some(last_move)
     }
 
-    fn check_winner_row(&self) -> Option<Winner> {
-        for r in 0..3 {
-            let row: &[Option<Rc<Player>>; 3] = &self.0[r];
-
-            let mut found_player = None;
-            for p in row {
-                match p {
-                    None => {
-                        if found_player.is_some() {
-                            found_player = None;
-                        }
-                        break;
-                    }
-                    Some(player) => {
-                        if found_player.is_some() {
-                            if found_player.unwrap() != player {
-                                found_player = None;
-                                break;
-                            }
-                        } else {
-                            found_player = Some(player);
-                        }
-                    }
-                }
-            }
-
-            if found_player.is_some() {
-                return Some(Winner(found_player.unwrap().clone()));
-            }
-        }
-
-        None
+    /// Replays the last move that was taken back, if there is one
+    pub fn redo(&mut self) -> Option<MoveResult> {
+        let next_move = self.undone.pop()?;
+        Some(self.place(next_move))
     }
 
-    fn check_winner_column(&self) -> Option<Winner> {
-        for c in 0..3 {
-            let column = &[&self.0[0][c], &self.0[1][c], &self.0[2][c]];
-
-            let mut found_player = None;
-            for p in column {
-                match p {
-                    None => {
-                        if found_player.is_some() {
-                            found_player = None;
-                        }
-                        break;
-                    }
-                    Some(player) => {
-                        if found_player.is_some() {
-                            if found_player.unwrap() != player {
-                                found_player = None;
-                                break;
-                            }
-                        } else {
-                            found_player = Some(player);
-                        }
-                    }
-                }
-            }
-
-            if found_player.is_some() {
-                return Some(Winner(found_player.unwrap().clone()));
+    /// Finds the line through (x_pos, y_pos) that wins the game for whoever owns that position.
+    /// The marks of the owner's teammates count towards the line.
+    pub fn winning_line_through(&self, x_pos: u8, y_pos: u8) -> Option<Vec<(u8, u8)>> {
+        let owner = self.get_at_pos(x_pos, y_pos).ok()?.as_ref()?;
+        self.config.line_through(x_pos, y_pos, |x, y| {
+            match self.get_at_pos(x, y) {
+                Ok(Some(player)) => player.is_teammate(owner),
+                _ => false
             }
-        }
+        })
+    }
 
-        None
+    pub fn check_winner(&self) -> Option<Winner> {
+        (0..self.config.size())
+            .map(|index| self.config.position_of(index))
+            .find_map(|(x, y)| {
+                let line = self.winning_line_through(x, y)?;
+                let player = self.get_at_pos(x, y).ok()?.clone()?;
+                Some(Winner(player, line))
+            })
     }
+}
 
-    fn check_winner_diagonal(&self) -> Option<Winner> {
-        if self.0[0][0].is_some() && self.0[0][0] == self.0[1][1] && self.0[1][1] == self.0[2][2] {
-            if let Some(winner) = &self.0[0][0] {
-                Some(Winner(winner.clone()))
-            } else {
-                unreachable!()
-            }
-        } else if self.0[0][2].is_some()
-            && self.0[0][2] == self.0[1][1]
-            && self.0[1][1] == self.0[2][0]
-        {
-            if let Some(winner) = &self.0[0][2] {
-                Some(Winner(winner.clone()))
-            } else {
-                unreachable!()
-            }
-        } else {
-            None
-        }
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Display for Board {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        for rows in &self.0 {
-            for o in rows {
+        for row in self.cells.chunks(self.config.width as usize) {
+            for o in row {
                 match o {
                     Some(o) => write!(f, "{} ", o.get_symbol())?,
                     None => write!(f, "  ")?
                 }
-            }.
-            writeln!(f, "")?;
+            }
+            writeln!(f)?;
         }
         Ok(())
     }
@@ -211,8 +369,8 @@ mod test {
 
     #[test]
     fn can_place_once() {
-        let mut builder = PlayerBuilder::new();
-        let player = Rc::new(
+        let builder = PlayerBuilder::new();
+        let player = Arc::new(
             builder.new_player(
                 'x',
                 Box::new(HumanController)
@@ -222,11 +380,8 @@ mod test {
         let mut board = Board::new();
 
         let mov = Move::new(1, 1, &player);
-        match board.make_move(mov) {
-            Err(err) => {
-                panic!("{:?}", err);
-            },
-            _ => {}
+        if let Err(err) = board.make_move(mov) {
+            panic!("{:?}", err);
         }
 
         match board.get_at_pos(1, 1) {
@@ -245,15 +400,12 @@ mod test {
     #[test]
     fn cant_replace() {
         let builder = PlayerBuilder::new();
-        let player = Rc::new(builder.new_player('x', Box::new(HumanController)).expect("Should be able to create player"));
+        let player = Arc::new(builder.new_player('x', Box::new(HumanController)).expect("Should be able to create player"));
         let mut board = Board::new();
 
         let mov = Move::new(1, 1, &player);
-        match board.make_move(mov) {
-            Err(err) => {
-                panic!("{:?}", err);
-            },
-            _ => {}
+        if let Err(err) = board.make_move(mov) {
+            panic!("{:?}", err);
         }
 
         let mov = Move::new(1, 1, &player);
@@ -273,7 +425,7 @@ mod test {
     #[test]
     fn out_of_bounds_check() {
         let builder = PlayerBuilder::new();
-        let player = Rc::new(builder.new_player('x', Box::new(HumanController)).expect("Should be able to create player"));
+        let player = Arc::new(builder.new_player('x', Box::new(HumanController)).expect("Should be able to create player"));
         let mut board = Board::new();
 
         let mov = Move::new(3, 3, &player);
@@ -287,4 +439,152 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn undo_and_redo() {
+        let builder = PlayerBuilder::new();
+        let x = Arc::new(builder.new_player('x', Box::new(HumanController)).expect("Should be able to create player"));
+        let o = Arc::new(builder.new_player('o', Box::new(HumanController)).expect("Should be able to create player"));
+        let mut board = Board::new();
+
+        assert!(board.undo().is_none());
+        board.make_move(Move::new(0, 0, &x)).unwrap();
+        board.make_move(Move::new(1, 1, &o)).unwrap();
+        board.make_move(Move::new(0, 1, &x)).unwrap();
+
+        let undone = board.undo().expect("There is a move to undo");
+        assert_eq!((undone.x_pos, undone.y_pos), (0, 1));
+        assert!(board.get_at_pos(0, 1).unwrap().is_none());
+        board.undo().unwrap();
+        assert_eq!(board.history().len(), 1);
+        assert_eq!(board.filled_positions(), 1);
+
+        assert!(board.redo().unwrap().unwrap().is_none());
+        assert_eq!(board.last_move().map(|m| (m.x_pos, m.y_pos)), Some((1, 1)));
+        assert_eq!(board.get_at_pos(1, 1).unwrap().as_ref(), Some(&o));
+        assert_eq!(board.next_redo().map(|m| (m.x_pos, m.y_pos)), Some((0, 1)));
+
+        // Playing a different move forgets what could have been redone
+        board.make_move(Move::new(2, 2, &x)).unwrap();
+        assert!(board.redo().is_none());
+        let history: Vec<_> = board.history().iter().map(|m| (m.x_pos, m.y_pos, *m.player.get_symbol())).collect();
+        assert_eq!(history, vec![(0, 0, 'x'), (1, 1, 'o'), (2, 2, 'x')]);
+    }
+
+    #[test]
+    fn hashes_follow_the_marks_on_the_board() {
+        let builder = PlayerBuilder::new();
+        let x = Arc::new(builder.new_player('x', Box::new(HumanController)).expect("Should be able to create player"));
+        let o = Arc::new(builder.new_player('o', Box::new(HumanController)).expect("Should be able to create player"));
+
+        let mut first = Board::new();
+        let mut second = Board::new();
+        assert_eq!(first.hash(), 0);
+        for &(x_pos, y_pos, player) in &[(0, 0, &x), (1, 1, &o), (2, 2, &x)] {
+            first.make_move(Move::new(x_pos, y_pos, player)).unwrap();
+        }
+        for &(x_pos, y_pos, player) in &[(2, 2, &x), (1, 1, &o), (0, 0, &x)] {
+            second.make_move(Move::new(x_pos, y_pos, player)).unwrap();
+        }
+        assert_eq!(first.hash(), second.hash());
+
+        // The same cells with the marks swapped are a different position
+        let mut swapped = Board::new();
+        for &(x_pos, y_pos, player) in &[(0, 0, &o), (1, 1, &x), (2, 2, &o)] {
+            swapped.make_move(Move::new(x_pos, y_pos, player)).unwrap();
+        }
+        assert_ne!(first.hash(), swapped.hash());
+
+        let hash = first.hash();
+        first.undo();
+        assert_ne!(first.hash(), hash);
+        first.redo();
+        assert_eq!(first.hash(), hash);
+        first.undo();
+        first.undo();
+        first.undo();
+        assert_eq!(first.hash(), 0);
+    }
+
+    #[test]
+    fn illegal_moves_are_not_recorded() {
+        let builder = PlayerBuilder::new();
+        let x = Arc::new(builder.new_player('x', Box::new(HumanController)).expect("Should be able to create player"));
+        let mut board = Board::new();
+
+        board.make_move(Move::new(0, 0, &x)).unwrap();
+        assert!(board.make_move(Move::new(0, 0, &x)).is_err());
+        assert!(board.make_move(Move::new(5, 0, &x)).is_err());
+        assert_eq!(board.history().len(), 1);
+    }
+
+    #[test]
+    fn invalid_configs_rejected() {
+        assert_eq!(BoardConfig::new(0, 3, 3), Err(BoardConfigError::EmptyBoard));
+        assert_eq!(BoardConfig::new(3, 3, 4), Err(BoardConfigError::WinLengthTooLong(4)));
+        assert!(BoardConfig::new(4, 2, 4).is_ok());
+    }
+
+    #[test]
+    fn rectangular_board_bounds() {
+        let builder = PlayerBuilder::new();
+        let player = Arc::new(builder.new_player('x', Box::new(HumanController)).expect("Should be able to create player"));
+        let mut board = Board::with_config(BoardConfig::new(5, 2, 3).unwrap());
+
+        assert_eq!(board.get_open_positions().len(), 10);
+        assert!(board.make_move(Move::new(4, 1, &player)).is_ok());
+        match board.make_move(Move::new(1, 2, &player)) {
+            Err(MoveError::OutOfBounds(1, 2)) => { }
+            _ => panic!("Should be out of bounds")
+        }
+        assert_eq!(board.filled_positions(), 1);
+        assert!(!board.get_open_positions().contains(&(4, 1)));
+    }
+
+    #[test]
+    fn k_in_a_row_wins() {
+        let builder = PlayerBuilder::new();
+        let x = Arc::new(builder.new_player('x', Box::new(HumanController)).expect("Should be able to create player"));
+        let o = Arc::new(builder.new_player('o', Box::new(HumanController)).expect("Should be able to create player"));
+        let mut board = Board::with_config(BoardConfig::square(15, 5).unwrap());
+
+        for i in 0..4 {
+            let result = board.make_move(Move::new(3 + i, 10 - i, &x)).expect("Move should be legal");
+            assert!(result.is_none(), "Four in a row should not win");
+            board.make_move(Move::new(i, 0, &o)).expect("Move should be legal");
+        }
+
+        match board.make_move(Move::new(7, 6, &x)) {
+            Ok(Some(winner)) => {
+                assert_eq!(winner.player(), &x);
+                assert_eq!(winner.line().len(), 5);
+            },
+            _ => panic!("Five in a row on the anti-diagonal should win")
+        }
+        assert_eq!(
+            board.winning_line_through(5, 8),
+            Some(vec![(3, 10), (4, 9), (5, 8), (6, 7), (7, 6)])
+        );
+    }
+
+    #[test]
+    fn standard_lines_win() {
+        let builder = PlayerBuilder::new();
+        let x = Arc::new(builder.new_player('x', Box::new(HumanController)).expect("Should be able to create player"));
+        let lines: [[(u8, u8); 3]; 4] = [
+            [(0, 1), (1, 1), (2, 1)],
+            [(2, 0), (2, 1), (2, 2)],
+            [(0, 0), (1, 1), (2, 2)],
+            [(2, 0), (1, 1), (0, 2)],
+        ];
+
+        for line in &lines {
+            let mut board = Board::new();
+            board.make_move(Move::new(line[0].0, line[0].1, &x)).unwrap();
+            board.make_move(Move::new(line[1].0, line[1].1, &x)).unwrap();
+            assert!(board.check_winner().is_none());
+            assert!(board.make_move(Move::new(line[2].0, line[2].1, &x)).unwrap().is_some());
+            assert!(board.check_winner().is_some());
+        }
+    }
 }
@@ -0,0 +1,367 @@
+use crate::game::board::{Board, BoardConfig};
+use crate::game::board::position::Seat;
+use crate::game::player::Player;
+use crate::game::Move;
+
+use std::fmt::{Display, Formatter};
+use std::fmt::Result as FmtResult;
+use std::sync::Arc;
+
+/// Every possible winning line on a board, as bitmasks of the cells they cover
+#[derive(Debug, PartialEq, Eq)]
+pub struct WinMasks {
+    config: BoardConfig,
+    lines: Vec<u128>,
+    /// For every cell, the lines that pass through it
+    through_cell: Vec<Vec<u128>>,
+}
+
+impl WinMasks {
+    pub fn new(config: BoardConfig) -> Result<Self, BoardTooLarge> {
+        if config.size() > BitBoard::MAX_CELLS {
+            return Err(BoardTooLarge(config.size()));
+        }
+
+        const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+        let width = config.width() as i32;
+        let height = config.height() as i32;
+        let length = config.win_length() as i32;
+
+        let mut lines = vec![];
+        let mut through_cell = vec![vec![]; config.size()];
+        for y in 0..height {
+            for x in 0..width {
+                for &(dx, dy) in &DIRECTIONS {
+                    let end_x = x + dx * (length - 1);
+                    let end_y = y + dy * (length - 1);
+                    if end_x < 0 || end_x >= width || end_y < 0 || end_y >= height {
+                        continue;
+                    }
+                    // A line of one cell would otherwise be found once per direction
+                    if length == 1 && (dx, dy) != (1, 0) {
+                        continue;
+                    }
+
+                    let cells: Vec<usize> = (0..length)
+                        .map(|i| config.index_of((x + dx * i) as u8, (y + dy * i) as u8))
+                        .collect();
+                    let mask = cells.iter().fold(0u128, |mask, &index| mask | 1 << index);
+                    lines.push(mask);
+                    for index in cells {
+                        through_cell[index].push(mask);
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            config,
+            lines,
+            through_cell,
+        })
+    }
+
+    pub fn config(&self) -> &BoardConfig {
+        &self.config
+    }
+
+    pub fn lines(&self) -> &[u128] {
+        &self.lines
+    }
+
+    pub fn through_cell(&self, index: usize) -> &[u128] {
+        &self.through_cell[index]
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BoardTooLarge(usize);
+
+impl Display for BoardTooLarge {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "A board with {} cells is too large for a bitboard, which holds at most {}", self.0, BitBoard::MAX_CELLS)
+    }
+}
+
+impl std::error::Error for BoardTooLarge {}
+
+/// A compact position with one bitmask of occupied cells per seat. Cell `i` of the row-major
+/// order is bit `i`. Cloning only copies the two masks and shares the win masks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitBoard {
+    seats: [u128; 2],
+    to_move: Seat,
+    win_masks: Arc<WinMasks>,
+}
+
+impl BitBoard {
+    pub const MAX_CELLS: usize = 128;
+
+    pub fn new(config: BoardConfig) -> Result<Self, BoardTooLarge> {
+        Ok(Self::with_masks(Arc::new(WinMasks::new(config)?)))
+    }
+
+    /// Creates an empty board sharing already computed win masks
+    pub fn with_masks(win_masks: Arc<WinMasks>) -> Self {
+        Self {
+            seats: [0, 0],
+            to_move: 0,
+            win_masks,
+        }
+    }
+
+    /// Creates a bitboard where `player` and their teammates are in seat 0, and `player` is the
+    /// next to move
+    pub fn from_board(board: &Board, player: &Arc<Player>) -> Result<Self, BoardTooLarge> {
+        let mut bitboard = Self::new(*board.config())?;
+        let config = *board.config();
+        for index in 0..config.size() {
+            let (x, y) = config.position_of(index);
+            match board.get_at_pos(x, y) {
+                Ok(Some(owner)) if owner.is_teammate(player) => bitboard.seats[0] |= 1 << index,
+                Ok(Some(_)) => bitboard.seats[1] |= 1 << index,
+                _ => {}
+            }
+        }
+        Ok(bitboard)
+    }
+
+    /// Creates a bitboard where the player who played the last move and their teammates are in
+    /// seat 1, and everyone else is in seat 0 and next to move
+    pub fn after_last_move(board: &Board) -> Result<Self, BoardTooLarge> {
+        let mut bitboard = Self::new(*board.config())?;
+        let last_player = board.last_move().map(|last| &last.player);
+        let config = *board.config();
+        for index in 0..config.size() {
+            let (x, y) = config.position_of(index);
+            match board.get_at_pos(x, y) {
+                Ok(Some(owner)) if last_player.is_some_and(|last| owner.is_teammate(last)) => bitboard.seats[1] |= 1 << index,
+                Ok(Some(_)) => bitboard.seats[0] |= 1 << index,
+                _ => {}
+            }
+        }
+        Ok(bitboard)
+    }
+
+    /// Creates a board with the marks of `players[seat]` for every seat. The history of the board
+    /// is in row-major order, as the order the moves were played in is not known.
+    pub fn to_board(&self, players: [&Arc<Player>; 2]) -> Board {
+        let config = *self.config();
+        let mut board = Board::with_config(config);
+        for index in 0..config.size() {
+            if let Some(seat) = self.get(index) {
+                let (x, y) = config.position_of(index);
+                board.make_move(Move::new(x, y, players[seat as usize]))
+                    .expect("Every cell is only filled once");
+            }
+        }
+        board
+    }
+
+    pub fn config(&self) -> &BoardConfig {
+        self.win_masks.config()
+    }
+
+    pub fn to_move(&self) -> Seat {
+        self.to_move
+    }
+
+    pub fn seat_mask(&self, seat: Seat) -> u128 {
+        self.seats[seat as usize]
+    }
+
+    pub fn occupied(&self) -> u128 {
+        self.seats[0] | self.seats[1]
+    }
+
+    /// A mask of every open cell
+    pub fn open(&self) -> u128 {
+        let size = self.config().size();
+        let all = if size == Self::MAX_CELLS { !0 } else { (1u128 << size) - 1 };
+        all & !self.occupied()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.open() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<Seat> {
+        let bit = 1u128 << index;
+        if self.seats[0] & bit != 0 {
+            Some(0)
+        } else if self.seats[1] & bit != 0 {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /// The indices of every open cell, lowest first
+    pub fn open_cells(&self) -> Cells {
+        Self::cells_of(self.open())
+    }
+
+    /// The indices of the set bits of `mask`, lowest first
+    pub fn cells_of(mask: u128) -> Cells {
+        Cells(mask)
+    }
+
+    /// Moves the mark in every cell `index` to cell `permutation[index]`
+    pub fn permuted(&self, permutation: &[usize]) -> Self {
+        let mut seats = [0; 2];
+        for (seat, mask) in self.seats.iter().enumerate() {
+            for index in Self::cells_of(*mask) {
+                seats[seat] |= 1 << permutation[index];
+            }
+        }
+
+        Self {
+            seats,
+            to_move: self.to_move,
+            win_masks: self.win_masks.clone(),
+        }
+    }
+
+    /// Places the mark of the seat to move at `index`, returning whether that move won the game.
+    /// The cell must be open.
+    pub fn play(&mut self, index: usize) -> bool {
+        debug_assert!(self.get(index).is_none(), "Cell {} is already filled", index);
+        let seat = self.to_move as usize;
+        self.seats[seat] |= 1 << index;
+        self.to_move = 1 - self.to_move;
+
+        let marks = self.seats[seat];
+        self.win_masks.through_cell(index).iter().any(|&line| line & !marks == 0)
+    }
+
+    /// Takes back the move at `index`
+    pub fn undo(&mut self, index: usize) {
+        let bit = 1u128 << index;
+        for seat in 0..2 {
+            if self.seats[seat] & bit != 0 {
+                self.seats[seat] &= !bit;
+                self.to_move = seat as Seat;
+            }
+        }
+    }
+
+    /// Finds the seat that has a winning line anywhere on the board
+    pub fn winner(&self) -> Option<Seat> {
+        (0..2).find(|&seat| {
+            let marks = self.seats[seat as usize];
+            self.win_masks.lines().iter().any(|&line| line & !marks == 0)
+        })
+    }
+}
+
+/// Iterates over the indices of the set bits of a mask
+pub struct Cells(u128);
+
+impl Iterator for Cells {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let index = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::player::PlayerBuilder;
+    use crate::game::player::controllers::HumanController;
+
+    #[test]
+    fn standard_board_has_eight_lines() {
+        let masks = WinMasks::new(BoardConfig::default()).unwrap();
+        assert_eq!(masks.lines().len(), 8);
+        // The center is on both diagonals, the middle row and the middle column
+        assert_eq!(masks.through_cell(4).len(), 4);
+        assert_eq!(masks.through_cell(1).len(), 2);
+    }
+
+    #[test]
+    fn too_large_boards_are_rejected() {
+        assert_eq!(BitBoard::new(BoardConfig::square(12, 5).unwrap()), Err(BoardTooLarge(144)));
+        assert!(BitBoard::new(BoardConfig::new(16, 8, 5).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn play_undo_and_win() {
+        let mut bitboard = BitBoard::new(BoardConfig::square(4, 3).unwrap()).unwrap();
+        // Seat 0 builds an anti-diagonal from (3, 0), seat 1 plays along the bottom row
+        for &(x, y) in &[(3, 0), (0, 3), (2, 1)] {
+            let index = bitboard.config().index_of(x, y);
+            assert!(!bitboard.play(index));
+        }
+        assert_eq!(bitboard.to_move(), 1);
+        assert!(!bitboard.play(bitboard.config().index_of(1, 3)));
+
+        let winning = bitboard.config().index_of(1, 2);
+        assert!(bitboard.play(winning));
+        assert_eq!(bitboard.winner(), Some(0));
+
+        bitboard.undo(winning);
+        assert_eq!(bitboard.winner(), None);
+        assert_eq!(bitboard.to_move(), 0);
+        assert_eq!(bitboard.open_cells().count(), 12);
+        assert!(!bitboard.open_cells().any(|index| index == bitboard.config().index_of(3, 0)));
+    }
+
+    #[test]
+    fn converts_to_and_from_board() {
+        let builder = PlayerBuilder::new();
+        let x = Arc::new(builder.new_player('x', HumanController.into()).unwrap());
+        let o = Arc::new(builder.new_player('o', HumanController.into()).unwrap());
+
+        let mut board = Board::with_config(BoardConfig::new(5, 4, 4).unwrap());
+        for &(x_pos, y_pos, player) in &[(0, 0, &x), (4, 3, &o), (2, 1, &x), (1, 3, &o)] {
+            board.make_move(Move::new(x_pos, y_pos, player)).unwrap();
+        }
+
+        let bitboard = BitBoard::from_board(&board, &o).unwrap();
+        assert_eq!(bitboard.seat_mask(0).count_ones(), 2);
+        assert_eq!(bitboard.get(board.config().index_of(4, 3)), Some(0));
+        assert_eq!(bitboard.get(board.config().index_of(2, 1)), Some(1));
+
+        let round_trip = bitboard.to_board([&o, &x]);
+        for index in 0..board.config().size() {
+            let (x_pos, y_pos) = board.config().position_of(index);
+            assert_eq!(round_trip.get_at_pos(x_pos, y_pos).unwrap(), board.get_at_pos(x_pos, y_pos).unwrap());
+        }
+    }
+    #[test]
+    fn teammates_share_a_seat() {
+        let builder = PlayerBuilder::new();
+        let mut players = ['x', 'o', '+', '#'].iter()
+            .map(|&symbol| builder.new_player(symbol, HumanController.into()).unwrap())
+            .collect::<Vec<_>>();
+        let (red, blue) = (builder.new_team("red").unwrap(), builder.new_team("blue").unwrap());
+        for (index, player) in players.iter_mut().enumerate() {
+            builder.assign_team(player, if index % 2 == 0 { &red } else { &blue });
+        }
+        let players: Vec<_> = players.into_iter().map(Arc::new).collect();
+
+        // x o + # in turn, so red (x and +) and blue (o and #) have two marks each
+        let mut board = Board::new();
+        for (player, &(x_pos, y_pos)) in players.iter().zip(&[(0, 0), (1, 1), (1, 0), (2, 2)]) {
+            board.make_move(Move::new(x_pos, y_pos, player)).unwrap();
+        }
+
+        let bitboard = BitBoard::from_board(&board, &players[2]).unwrap();
+        assert_eq!(bitboard.seat_mask(0).count_ones(), 2);
+        assert_eq!(bitboard.get(board.config().index_of(0, 0)), Some(0));
+
+        // Blue moved last, so red is next to move
+        let bitboard = BitBoard::after_last_move(&board).unwrap();
+        assert_eq!(bitboard.get(board.config().index_of(1, 0)), Some(0));
+        assert_eq!(bitboard.get(board.config().index_of(1, 1)), Some(1));
+        assert_eq!(bitboard.seat_mask(1).count_ones(), 2);
+    }
+}
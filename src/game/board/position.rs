@@ -0,0 +1,173 @@
+use crate::game::board::{Board, BoardConfig, zobrist};
+use crate::game::player::Player;
+use std::sync::Arc;
+
+/// A seat at the table. Seat 0 is always the player whose point of view a position was created from
+pub type Seat = u8;
+
+/// A lightweight copy of a `Board` used by search based controllers, where
+/// players are replaced by their seat so moves can be made and taken back cheaply.
+/// The player's teammates share seat 0 with them, and everyone else is in seat 1, so positions
+/// only describe games between two sides.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Position {
+    config: BoardConfig,
+    cells: Vec<Option<Seat>>,
+    to_move: Seat,
+    hash: u64,
+}
+
+impl Position {
+    pub const SEATS: u8 = 2;
+
+    pub fn new(config: BoardConfig) -> Self {
+        Self {
+            config,
+            cells: vec![None; config.size()],
+            to_move: 0,
+            hash: zobrist::turn_key(0),
+        }
+    }
+
+    /// Creates a position where `player` and their teammates are in seat 0, and `player` is the
+    /// next to move
+    pub fn from_board(board: &Board, player: &Arc<Player>) -> Self {
+        let config = *board.config();
+        let cells = (0..config.size())
+            .map(|index| {
+                let (x, y) = config.position_of(index);
+                match board.get_at_pos(x, y) {
+                    Ok(Some(owner)) if owner.is_teammate(player) => Some(0),
+                    Ok(Some(_)) => Some(1),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            config,
+            cells,
+            to_move: 0,
+            hash: board.hash() ^ zobrist::player_key(player.id() as u32 as u64) ^ zobrist::turn_key(0),
+        }
+    }
+
+    pub fn config(&self) -> &BoardConfig {
+        &self.config
+    }
+
+    pub fn to_move(&self) -> Seat {
+        self.to_move
+    }
+
+    /// The Zobrist hash of the board the position was created from and whose point of view it
+    /// has, the marks placed since and the seat to move
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn get(&self, x_pos: u8, y_pos: u8) -> Option<Seat> {
+        self.cells[self.config.index_of(x_pos, y_pos)]
+    }
+
+    pub fn open_positions(&self) -> Vec<(u8, u8)> {
+        self.cells.iter()
+            .enumerate()
+            .filter(|(_, cell)| cell.is_none())
+            .map(|(index, _)| self.config.position_of(index))
+            .collect()
+    }
+
+    pub fn filled_positions(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.is_some()).count()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.cells.iter().all(Option::is_some)
+    }
+
+    /// Places the mark of the seat to move, returning whether that move won the game
+    ///
+    /// # Panics
+    /// If the position is already filled
+    pub fn play(&mut self, x_pos: u8, y_pos: u8) -> bool {
+        let index = self.config.index_of(x_pos, y_pos);
+        assert!(self.cells[index].is_none(), "({}, {}) is already filled", x_pos, y_pos);
+
+        let seat = self.to_move;
+        self.cells[index] = Some(seat);
+        self.to_move = (seat + 1) % Self::SEATS;
+        self.hash ^= zobrist::seat_key(index, seat as u64) ^ zobrist::turn_key(seat as u64) ^ zobrist::turn_key(self.to_move as u64);
+        self.config.line_through(x_pos, y_pos, |x, y| self.get(x, y) == Some(seat)).is_some()
+    }
+
+    /// Takes back the move at (x_pos, y_pos), which must have been the last move played
+    pub fn undo(&mut self, x_pos: u8, y_pos: u8) {
+        let index = self.config.index_of(x_pos, y_pos);
+        if let Some(seat) = self.cells[index].take() {
+            self.hash ^= zobrist::seat_key(index, seat as u64) ^ zobrist::turn_key(self.to_move as u64) ^ zobrist::turn_key(seat as u64);
+            self.to_move = seat;
+        }
+    }
+
+    /// Finds the seat that has a winning line anywhere on the board
+    pub fn winner(&self) -> Option<Seat> {
+        (0..self.config.size())
+            .map(|index| self.config.position_of(index))
+            .find(|&(x, y)| {
+                let seat = self.get(x, y);
+                seat.is_some() && self.config.line_through(x, y, |x, y| self.get(x, y) == seat).is_some()
+            })
+            .and_then(|(x, y)| self.get(x, y))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::player::PlayerBuilder;
+    use crate::game::player::controllers::HumanController;
+    use crate::game::Move;
+
+    #[test]
+    fn play_and_undo() {
+        let mut position = Position::new(BoardConfig::default());
+
+        assert!(!position.play(0, 0));
+        assert!(!position.play(1, 0));
+        assert!(!position.play(0, 1));
+        assert!(!position.play(1, 1));
+        assert_eq!(position.to_move(), 0);
+        assert!(position.play(0, 2));
+        assert_eq!(position.winner(), Some(0));
+
+        let hash = position.hash();
+        position.undo(0, 2);
+        assert_ne!(position.hash(), hash);
+        assert_eq!(position.to_move(), 0);
+        assert_eq!(position.winner(), None);
+        assert_eq!(position.filled_positions(), 4);
+        assert_eq!(position.open_positions().len(), 5);
+    }
+
+    #[test]
+    fn hashes_start_from_the_board() {
+        let builder = PlayerBuilder::new();
+        let x = Arc::new(builder.new_player('x', HumanController.into()).unwrap());
+        let o = Arc::new(builder.new_player('o', HumanController.into()).unwrap());
+
+        let mut first = Board::new();
+        let mut second = Board::new();
+        for &(x_pos, y_pos, player) in &[(0, 0, &x), (1, 1, &o)] {
+            first.make_move(Move::new(x_pos, y_pos, player)).unwrap();
+        }
+        for &(x_pos, y_pos, player) in &[(1, 1, &o), (0, 0, &x)] {
+            second.make_move(Move::new(x_pos, y_pos, player)).unwrap();
+        }
+        let position = Position::from_board(&first, &x);
+        assert_eq!(position.hash(), Position::from_board(&second, &x).hash());
+
+        // The same board from the other player's point of view has the marks in the other seats
+        assert_ne!(position.hash(), Position::from_board(&first, &o).hash());
+    }
+}
@@ -0,0 +1,150 @@
+//! Deserialization of boards goes through these plain structs, so that invalid sizes and cells
+//! that don't match the history are rejected instead of breaking the board's invariants.
+
+use crate::game::board::{Board, BoardConfig, BoardConfigError};
+use crate::game::player::Player;
+use crate::game::Move;
+
+use serde::Deserialize;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct BoardConfigFields {
+    width: u8,
+    height: u8,
+    win_length: u8,
+}
+
+impl TryFrom<BoardConfigFields> for BoardConfig {
+    type Error = BoardConfigError;
+
+    fn try_from(fields: BoardConfigFields) -> Result<Self, BoardConfigError> {
+        BoardConfig::new(fields.width, fields.height, fields.win_length)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct BoardFields {
+    config: BoardConfig,
+    cells: Vec<Option<Arc<Player>>>,
+    history: Vec<Move>,
+    undone: Vec<Move>,
+}
+
+impl TryFrom<BoardFields> for Board {
+    type Error = String;
+
+    /// Replays the history on an empty board and checks it leads to the serialized cells
+    fn try_from(fields: BoardFields) -> Result<Self, String> {
+        let mut board = Board::with_config(fields.config);
+        for (index, played) in fields.history.into_iter().enumerate() {
+            board.place(played).map_err(|e| format!("Move {} of the history is illegal: {}", index + 1, e))?;
+        }
+
+        if board.cells != fields.cells {
+            return Err("The cells don't match the moves in the history".to_string());
+        }
+        board.undone = fields.undone;
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::board::{Board, BoardConfig, MoveError};
+    use crate::game::cycle::{CurrentPlayer, GameCycle, GameOutcome};
+    use crate::game::player::{Player, PlayerBuilder};
+    use crate::game::profile::{Color, Profile};
+    use crate::game::player::controllers::HumanController;
+    use crate::game::Move;
+    use std::sync::Arc;
+
+    fn players() -> (Arc<Player>, Arc<Player>) {
+        let builder = PlayerBuilder::new();
+        let x = Arc::new(builder.new_player('x', HumanController.into()).unwrap());
+        let o = Arc::new(builder.new_player('o', HumanController.into()).unwrap());
+        (x, o)
+    }
+
+    #[test]
+    fn boards_round_trip() {
+        let (x, o) = players();
+        let mut board = Board::with_config(BoardConfig::new(4, 3, 3).unwrap());
+        for &(x_pos, y_pos, player) in &[(1, 1, &x), (0, 0, &o), (3, 2, &x), (2, 0, &o)] {
+            board.make_move(Move::new(x_pos, y_pos, player)).unwrap();
+        }
+        board.undo();
+
+        let json = serde_json::to_string(&board).unwrap();
+        let loaded: Board = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.config(), board.config());
+        assert_eq!(loaded.to_string(), board.to_string());
+        assert_eq!(loaded.history().len(), 3);
+        // The hash isn't serialized, replaying the history restores it
+        assert_eq!(loaded.hash(), board.hash());
+        // Players keep their identity, so the loaded board's players equal the originals
+        assert_eq!(loaded.get_at_pos(1, 1).unwrap().as_ref(), Some(&x));
+        assert_eq!(loaded.next_redo().map(|undone| &undone.player), Some(&o));
+    }
+
+    #[test]
+    fn inconsistent_boards_are_rejected() {
+        let (x, _) = players();
+        let mut board = Board::new();
+        board.make_move(Move::new(1, 1, &x)).unwrap();
+
+        let mut json = serde_json::to_value(&board).unwrap();
+        json["cells"][4] = serde_json::Value::Null;
+        assert!(serde_json::from_value::<Board>(json).is_err());
+
+        let json = serde_json::json!({ "width": 2, "height": 2, "win_length": 3 });
+        assert!(serde_json::from_value::<BoardConfig>(json).is_err());
+    }
+
+    #[test]
+    fn moves_errors_and_outcomes_round_trip() {
+        let (x, o) = players();
+
+        let json = serde_json::to_string(&Move::new(2, 1, &o)).unwrap();
+        let loaded: Move = serde_json::from_str(&json).unwrap();
+        assert_eq!((loaded.x_pos, loaded.y_pos, &loaded.player), (2, 1, &o));
+
+        let json = serde_json::to_string(&MoveError::PositionAlreadyFilled(x.clone())).unwrap();
+        match serde_json::from_str(&json).unwrap() {
+            MoveError::PositionAlreadyFilled(player) => assert_eq!(player, x),
+            error => panic!("Expected the position to be filled, got {:?}", error)
+        }
+
+        let outcome = GameOutcome::Win { player: x.clone(), winning_line: vec![(0, 0), (1, 1), (2, 2)], move_number: 5, team: None };
+        let json = serde_json::to_string(&outcome).unwrap();
+        match serde_json::from_str(&json).unwrap() {
+            GameOutcome::Win { player, winning_line, move_number, .. } => {
+                assert_eq!(player, x);
+                assert_eq!(player.get_symbol(), &'x');
+                assert_eq!(winning_line, vec![(0, 0), (1, 1), (2, 2)]);
+                assert_eq!(move_number, 5);
+            },
+            outcome => panic!("Expected a win, got {:?}", outcome)
+        }
+    }
+
+    #[test]
+    fn deserialized_players_keep_their_profile_and_abandon_games() {
+        let builder = PlayerBuilder::new();
+        let profile = Profile::new("alice", 'a').with_display_name("Alice").with_color(Color::Red);
+        let alice = builder.new_profile_player(&profile, HumanController.into()).unwrap();
+        let bob = builder.new_player('b', HumanController.into()).unwrap();
+
+        let alice: Player = serde_json::from_str(&serde_json::to_string(&alice).unwrap()).unwrap();
+        let bob: Player = serde_json::from_str(&serde_json::to_string(&bob).unwrap()).unwrap();
+        assert_eq!((alice.to_string(), alice.name(), alice.color()), ("Alice".to_string(), Some("alice"), Some(Color::Red)));
+        assert_eq!((bob.to_string(), bob.color()), ("Player b".to_string(), None));
+
+        let mut cycle = GameCycle::with_players(vec![alice, bob], BoardConfig::default()).unwrap();
+        match cycle.cycle_from(CurrentPlayer::PLAYER1) {
+            GameOutcome::Aborted { reason } => assert_eq!(reason, "Alice was deserialized and has no controller to choose moves"),
+            outcome => panic!("Expected the game to be aborted, got {:?}", outcome)
+        }
+    }
+}
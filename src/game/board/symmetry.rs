@@ -0,0 +1,193 @@
+//! The rotations and reflections of a board. Square boards have 8 symmetries, while other
+//! rectangles only have the 4 that keep their width and height.
+
+use crate::game::board::{Board, BoardConfig};
+use crate::game::board::bitboard::BitBoard;
+use crate::game::Move;
+
+/// A rotation or reflection of the board. Rotations are clockwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirrors the board left to right
+    FlipHorizontal,
+    /// Mirrors the board top to bottom
+    FlipVertical,
+    /// Mirrors the board along the diagonal from the top left corner
+    FlipDiagonal,
+    /// Mirrors the board along the diagonal from the top right corner
+    FlipAntiDiagonal,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::FlipDiagonal,
+        Transform::FlipAntiDiagonal,
+    ];
+
+    /// Whether the transform maps a board with `config` onto itself, which only quarter turns and
+    /// diagonal reflections of non-square boards don't
+    pub fn preserves(self, config: &BoardConfig) -> bool {
+        match self {
+            Transform::Rotate90 | Transform::Rotate270 | Transform::FlipDiagonal | Transform::FlipAntiDiagonal => {
+                config.width() == config.height()
+            },
+            _ => true
+        }
+    }
+
+    /// Every transform that maps a board with `config` onto itself
+    pub fn all_for(config: &BoardConfig) -> Vec<Transform> {
+        Self::ALL.iter().copied().filter(|transform| transform.preserves(config)).collect()
+    }
+
+    /// The transform that undoes this one
+    pub fn inverse(self) -> Self {
+        match self {
+            Transform::Rotate90 => Transform::Rotate270,
+            Transform::Rotate270 => Transform::Rotate90,
+            other => other
+        }
+    }
+
+    /// Where (x_pos, y_pos) ends up. The transform must preserve `config`.
+    pub fn apply(self, config: &BoardConfig, x_pos: u8, y_pos: u8) -> (u8, u8) {
+        let last_x = config.width() - 1;
+        let last_y = config.height() - 1;
+        match self {
+            Transform::Identity => (x_pos, y_pos),
+            Transform::Rotate90 => (last_y - y_pos, x_pos),
+            Transform::Rotate180 => (last_x - x_pos, last_y - y_pos),
+            Transform::Rotate270 => (y_pos, last_x - x_pos),
+            Transform::FlipHorizontal => (last_x - x_pos, y_pos),
+            Transform::FlipVertical => (x_pos, last_y - y_pos),
+            Transform::FlipDiagonal => (y_pos, x_pos),
+            Transform::FlipAntiDiagonal => (last_y - y_pos, last_x - x_pos),
+        }
+    }
+
+    /// For every cell index, the index it ends up at
+    pub fn permutation(self, config: &BoardConfig) -> Vec<usize> {
+        (0..config.size())
+            .map(|index| {
+                let (x, y) = config.position_of(index);
+                let (x, y) = self.apply(config, x, y);
+                config.index_of(x, y)
+            })
+            .collect()
+    }
+}
+
+/// Transforms every move on the board, including the ones that can be redone
+pub fn transform_board(board: &Board, transform: Transform) -> Board {
+    let config = board.config;
+    assert!(transform.preserves(&config), "{:?} doesn't preserve a {}x{} board", transform, config.width(), config.height());
+
+    let moved = |played: &Move| {
+        let (x, y) = transform.apply(&config, played.x_pos, played.y_pos);
+        Move::new(x, y, &played.player)
+    };
+
+    let mut transformed = Board::with_config(config);
+    for played in &board.history {
+        transformed.place(moved(played)).expect("Transforms map distinct cells to distinct cells");
+    }
+    transformed.undone = board.undone.iter().map(moved).collect();
+    transformed
+}
+
+/// The canonical form of the board, which every board symmetric to it shares, along with the
+/// transform that maps the board to it. The canonical form has the smallest list of symbols in
+/// row-major order, with empty cells coming first.
+pub fn canonical_form(board: &Board) -> (Board, Transform) {
+    let symbols = |board: &Board| -> Vec<Option<char>> {
+        board.cells.iter().map(|cell| cell.as_ref().map(|player| *player.get_symbol())).collect()
+    };
+
+    Transform::all_for(board.config()).into_iter()
+        .map(|transform| (transform_board(board, transform), transform))
+        .min_by_key(|(transformed, _)| symbols(transformed))
+        .expect("The identity always preserves the board")
+}
+
+/// Finds canonical forms of bitboards, with the permutations of every transform computed once
+pub struct Symmetries {
+    transforms: Vec<(Transform, Vec<usize>)>,
+}
+
+impl Symmetries {
+    pub fn new(config: &BoardConfig) -> Self {
+        Self {
+            transforms: Transform::all_for(config).into_iter()
+                .map(|transform| (transform, transform.permutation(config)))
+                .collect(),
+        }
+    }
+
+    pub fn transforms(&self) -> impl Iterator<Item = Transform> + '_ {
+        self.transforms.iter().map(|(transform, _)| *transform)
+    }
+
+    /// The canonical form of a bitboard and the transform that maps it there. The canonical form
+    /// has the smallest masks, comparing seat 0 first.
+    pub fn canonical_form(&self, position: &BitBoard) -> (BitBoard, Transform) {
+        self.transforms.iter()
+            .map(|(transform, permutation)| (position.permuted(permutation), *transform))
+            .min_by_key(|(permuted, _)| (permuted.seat_mask(0), permuted.seat_mask(1)))
+            .expect("The identity always preserves the board")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::player::PlayerBuilder;
+    use crate::game::player::controllers::HumanController;
+    use std::sync::Arc;
+
+    #[test]
+    fn transforms_are_undone_by_their_inverse() {
+        for &config in &[BoardConfig::square(4, 3).unwrap(), BoardConfig::new(5, 3, 3).unwrap()] {
+            let transforms = Transform::all_for(&config);
+            assert_eq!(transforms.len(), if config.width() == config.height() { 8 } else { 4 });
+
+            for transform in transforms {
+                let forward = transform.permutation(&config);
+                let backward = transform.inverse().permutation(&config);
+                for index in 0..config.size() {
+                    assert_eq!(backward[forward[index]], index, "{:?} on {:?}", transform, config);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn symmetric_boards_share_a_canonical_form() {
+        let builder = PlayerBuilder::new();
+        let x = Arc::new(builder.new_player('x', HumanController.into()).unwrap());
+        let o = Arc::new(builder.new_player('o', HumanController.into()).unwrap());
+
+        let mut board = Board::with_config(BoardConfig::square(4, 3).unwrap());
+        for &(x_pos, y_pos, player) in &[(1, 0, &x), (3, 1, &o), (0, 3, &x)] {
+            board.make_move(Move::new(x_pos, y_pos, player)).unwrap();
+        }
+        let (canonical, _) = canonical_form(&board);
+
+        for &transform in &Transform::ALL {
+            let symmetric = transform_board(&board, transform);
+            let (symmetric_canonical, to_canonical) = canonical_form(&symmetric);
+            assert_eq!(symmetric_canonical.to_string(), canonical.to_string());
+            assert_eq!(transform_board(&symmetric, to_canonical).to_string(), canonical.to_string());
+            assert_eq!(symmetric.history().len(), 3);
+        }
+    }
+}
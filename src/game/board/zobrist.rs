@@ -0,0 +1,36 @@
+//! Zobrist hashing: the hash of a board or position is the xor of a random looking key for every filled cell,
+//! so placing or removing a mark updates the hash with a single xor.
+//!
+//! Keys are derived from the cell and its owner instead of being drawn from a table, so boards
+//! of any size get the same hashes in every run.
+
+/// The key for the mark of the player with the id `owner` in cell `index` of a board
+pub fn cell_key(index: usize, owner: u64) -> u64 {
+    splitmix64(((index as u64) << 32) ^ owner)
+}
+
+/// The key for the mark of `seat` in cell `index` of a position. Seats are numbered like player
+/// ids, so these are kept apart from the keys of boards.
+pub fn seat_key(index: usize, seat: u64) -> u64 {
+    splitmix64(cell_key(index, seat))
+}
+
+/// The key for the point of view of the player with the id `player`. Positions created from the
+/// same board for different players put different marks in seat 0, so they need different hashes.
+pub fn player_key(player: u64) -> u64 {
+    splitmix64(turn_key(player))
+}
+
+/// The key for `seat` being the next to move. The same marks with a different player to move
+/// are a different position to a search.
+pub fn turn_key(seat: u64) -> u64 {
+    splitmix64(!seat)
+}
+
+/// The finalizer of the SplitMix64 generator, which spreads every input bit over the whole output
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
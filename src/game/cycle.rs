@@ -1,31 +1,284 @@
-use crate::game::player::{Player, Controller, SelfController};
+use crate::game::player::{Controller, Player, SelfController, Team};
 use crate::game::board::*;
-use std::rc::Rc;
-use rand::{random, Rng};
-use rand::distributions::{Distribution, Standard};
-use crate::game::cycle::CurrentPlayer::{Player2, Player1};
+use crate::game::{render, Action};
+use crate::game::record::{GameRecord, RecordError, RecordResult, RecordedTeam};
+use std::sync::Arc;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use std::fmt::{Display, Formatter};
+use std::fmt::Result as FmtResult;
+use std::str::FromStr;
 
+/// How many illegal moves in a row a player can attempt before the game is abandoned
+pub const MAX_ILLEGAL_MOVES: u32 = 10;
 
+/// Plays games between two or more players, who take turns in the order they were given
+pub struct GameCycle {
+    players: Vec<Arc<Player>>,
+    board: Board,
+    display: bool,
+    rng: StdRng,
+    seed: Option<u64>,
+    starting_rule: StartingRule,
+    last_game: Option<LastGame>
+}
 
+/// A player's place in the turn order, from 0 for player 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CurrentPlayer(usize);
 
-pub struct GameCycle {
-    player1: Rc<Player>,
-    player2: Rc<Player>,
-    board: Board
+impl CurrentPlayer {
+    pub const PLAYER1: CurrentPlayer = CurrentPlayer(0);
+    pub const PLAYER2: CurrentPlayer = CurrentPlayer(1);
+
+    /// The player at `index` in the turn order of a game between `players` players
+    pub fn new(index: usize, players: usize) -> Result<CurrentPlayer, NoSuchPlayer> {
+        if index < players {
+            Ok(CurrentPlayer(index))
+        } else {
+            Err(NoSuchPlayer { index, players })
+        }
+    }
+
+    pub fn index(self) -> usize {
+        self.0
+    }
+
+    /// The player whose turn comes after this one in a game between `players` players
+    pub fn next(self, players: usize) -> CurrentPlayer {
+        CurrentPlayer((self.0 + 1) % players)
+    }
+
+    fn random<R: Rng>(players: usize, rng: &mut R) -> CurrentPlayer {
+        CurrentPlayer(rng.gen_range(0, players))
+    }
+}
+
+/// A place in the turn order past the last player
+#[derive(Debug, PartialEq, Eq)]
+pub struct NoSuchPlayer {
+    pub index: usize,
+    pub players: usize,
 }
 
-enum CurrentPlayer {
-    Player1,
-    Player2
+impl Display for NoSuchPlayer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "There is no player {} in a game between {} players", self.index + 1, self.players)
+    }
 }
 
+impl std::error::Error for NoSuchPlayer {}
 
-impl Distribution<CurrentPlayer> for Standard {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> CurrentPlayer {
-        if rng.gen_bool(0.5) {
-            CurrentPlayer::Player1
-        } else {
-            CurrentPlayer::Player2
+/// Who moves first in the games played by a [`GameCycle`](struct.GameCycle.html)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartingRule {
+    /// The player at this index in the turn order, from 0 for player 1
+    Player(usize),
+    /// A random player before every game
+    Random,
+    /// Players take turns starting. The first game is started by a random player.
+    Alternate,
+    /// The loser of the last game starts the next one, or with more than two players, whoever
+    /// moves after the winner. After a draw the players take turns, and the first game is
+    /// started by a random player.
+    LoserStarts,
+}
+
+/// Why players couldn't be split into teams
+#[derive(Debug, PartialEq, Eq)]
+pub enum TeamError {
+    /// The player with this symbol isn't on a team
+    Unassigned(char),
+    TooFewTeams,
+    /// Every team needs the same number of players, so that turns can alternate between teams
+    UnevenTeams,
+    UnsupportedSides(UnsupportedSides),
+}
+
+impl Display for TeamError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            TeamError::Unassigned(symbol) => write!(f, "The player with the symbol '{}' isn't on a team", symbol),
+            TeamError::TooFewTeams => write!(f, "A team game needs at least two teams"),
+            TeamError::UnevenTeams => write!(f, "Every team needs the same number of players"),
+            TeamError::UnsupportedSides(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for TeamError {}
+
+impl From<UnsupportedSides> for TeamError {
+    fn from(error: UnsupportedSides) -> Self {
+        TeamError::UnsupportedSides(error)
+    }
+}
+
+/// A player whose controller can't play a game between this many sides, such as a computer
+/// player that searches as if there were only two
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnsupportedSides {
+    pub symbol: char,
+    pub sides: usize,
+}
+
+impl Display for UnsupportedSides {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "The player with the symbol '{}' can only play games between two sides, not {}", self.symbol, self.sides)
+    }
+}
+
+impl std::error::Error for UnsupportedSides {}
+
+/// Who started and won the last game played by a cycle
+#[derive(Debug, Clone, Copy)]
+struct LastGame {
+    starter: CurrentPlayer,
+    winner: Option<CurrentPlayer>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameOutcome {
+    Win {
+        player: Arc<Player>,
+        winning_line: Vec<(u8, u8)>,
+        /// The number of moves played in the game, including the winning move
+        move_number: usize,
+        /// The team that won along with the player, if they play on one
+        team: Option<WinningTeam>,
+    },
+    Draw,
+    Aborted {
+        reason: String
+    },
+}
+
+/// A team that won a game, and every player on it
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WinningTeam {
+    pub team: Team,
+    pub members: Vec<Arc<Player>>,
+}
+
+impl GameOutcome {
+    /// The win of the player that completed a line after `move_number` moves, along with their
+    /// team among `players`
+    pub fn win(winner: Winner, move_number: usize, players: &[Arc<Player>]) -> Self {
+        let (player, winning_line) = winner.into_parts();
+        let team = player.team().map(|team| WinningTeam {
+            team: team.clone(),
+            members: players.iter().filter(|other| other.is_teammate(&player)).cloned().collect(),
+        });
+
+        GameOutcome::Win {
+            player,
+            winning_line,
+            move_number,
+            team,
+        }
+    }
+
+    pub fn winner(&self) -> Option<&Arc<Player>> {
+        match self {
+            GameOutcome::Win { player, .. } => Some(player),
+            _ => None
+        }
+    }
+
+    pub fn winning_team(&self) -> Option<&WinningTeam> {
+        match self {
+            GameOutcome::Win { team, .. } => team.as_ref(),
+            _ => None
+        }
+    }
+}
+
+impl Display for GameOutcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            GameOutcome::Win { player, winning_line, move_number, team } => {
+                match team {
+                    Some(WinningTeam { team, members }) => {
+                        let members: Vec<String> = members.iter().map(ToString::to_string).collect();
+                        write!(f, "{} ({}) wins on move {} with the line", team, members.join(", "), move_number)?;
+                    },
+                    None => write!(f, "{} wins on move {} with the line", player, move_number)?
+                }
+                for (x, y) in winning_line {
+                    write!(f, " ({}, {})", x, y)?;
+                }
+                Ok(())
+            },
+            GameOutcome::Draw => write!(f, "The game is a draw"),
+            GameOutcome::Aborted { reason } => write!(f, "The game was aborted: {}", reason)
+        }
+    }
+}
+
+
+impl StartingRule {
+    pub const ALL: [StartingRule; 5] = [
+        StartingRule::Player(0),
+        StartingRule::Player(1),
+        StartingRule::Random,
+        StartingRule::Alternate,
+        StartingRule::LoserStarts,
+    ];
+
+    /// The player to start a game between `players` players, given how the last game in the
+    /// series went
+    fn starter<R: Rng>(self, last_game: Option<LastGame>, players: usize, rng: &mut R) -> CurrentPlayer {
+        match (self, last_game) {
+            (StartingRule::Player(index), _) => {
+                CurrentPlayer::new(index, players).expect("Starting rules are checked against the players")
+            },
+            (StartingRule::LoserStarts, Some(LastGame { winner: Some(winner), .. })) => winner.next(players),
+            (StartingRule::Alternate, Some(last)) | (StartingRule::LoserStarts, Some(last)) => last.starter.next(players),
+            (StartingRule::Random, _) | (_, None) => CurrentPlayer::random(players, rng)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownStartingRule(String);
+
+impl Display for UnknownStartingRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "Unknown starting rule \"{}\", expected a player number, random, alternate or loser", self.0)
+    }
+}
+
+impl std::error::Error for UnknownStartingRule {}
+
+impl FromStr for StartingRule {
+    type Err = UnknownStartingRule;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lowercase = s.to_lowercase();
+        let number = lowercase.strip_prefix("player").unwrap_or(&lowercase);
+        match number.parse::<usize>() {
+            Ok(number) if number > 0 => return Ok(StartingRule::Player(number - 1)),
+            _ => {}
+        }
+
+        match lowercase.as_str() {
+            "random" => Ok(StartingRule::Random),
+            "alternate" => Ok(StartingRule::Alternate),
+            "loser" => Ok(StartingRule::LoserStarts),
+            _ => Err(UnknownStartingRule(s.to_string()))
+        }
+    }
+}
+
+impl Display for StartingRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            StartingRule::Player(index) => write!(f, "{}", index + 1),
+            StartingRule::Random => write!(f, "random"),
+            StartingRule::Alternate => write!(f, "alternate"),
+            StartingRule::LoserStarts => write!(f, "loser"),
         }
     }
 }
@@ -33,62 +286,764 @@ impl Distribution<CurrentPlayer> for Standard {
 impl GameCycle {
 
     pub fn new(player1: Player, player2: Player) -> Self {
-        Self {
-            player1: Rc::new(player1),
-            player2: Rc::new(player2),
-            board: Board::new()
+        Self::with_config(player1, player2, BoardConfig::default())
+    }
+
+    pub fn with_config(player1: Player, player2: Player, config: BoardConfig) -> Self {
+        Self::with_players(vec![player1, player2], config).expect("Every controller plays games between two sides")
+    }
+
+    /// A cycle for any number of players, who take turns in the order given. Fails if a player
+    /// can't play a game between as many sides as there are.
+    ///
+    /// # Panics
+    /// If there are fewer than two players
+    pub fn with_players(players: Vec<Player>, config: BoardConfig) -> Result<Self, UnsupportedSides> {
+        Self::with_board(players, Board::with_config(config))
+    }
+
+    /// A cycle for players on teams, where turns alternate between the teams. The teams take
+    /// turns in the order their first players were given, and each team's players take turns in
+    /// the order they were given.
+    pub fn with_teams(players: Vec<Player>, config: BoardConfig) -> Result<Self, TeamError> {
+        let mut teams: Vec<(Team, Vec<Player>)> = vec![];
+        for player in players {
+            let team = player.team().cloned().ok_or_else(|| TeamError::Unassigned(*player.get_symbol()))?;
+            match teams.iter_mut().find(|(other, _)| *other == team) {
+                Some((_, members)) => members.push(player),
+                None => teams.push((team, vec![player])),
+            }
+        }
+        if teams.len() < 2 {
+            return Err(TeamError::TooFewTeams);
+        }
+        let team_size = teams[0].1.len();
+        if teams.iter().any(|(_, members)| members.len() != team_size) {
+            return Err(TeamError::UnevenTeams);
         }
+
+        let mut members: Vec<_> = teams.into_iter().map(|(_, members)| members.into_iter()).collect();
+        let ordered = (0..team_size)
+            .flat_map(|_| members.iter_mut().map(|team| team.next().expect("Every team has the same size")).collect::<Vec<_>>())
+            .collect();
+        Ok(Self::with_players(ordered, config)?)
     }
 
-    fn next_player(current: &mut CurrentPlayer) {
-        *current = match current {
-            CurrentPlayer::Player1 => Player2,
-            CurrentPlayer::Player2 => Player1
+    fn with_board(players: Vec<Player>, board: Board) -> Result<Self, UnsupportedSides> {
+        assert!(players.len() >= 2, "A game needs at least two players");
+        let players: Vec<Arc<Player>> = players.into_iter().map(Arc::new).collect();
+
+        // Every player without an earlier teammate starts another side
+        let sides = players.iter()
+            .enumerate()
+            .filter(|(index, player)| !players[..*index].iter().any(|other| other.is_teammate(player)))
+            .count();
+        if let Some(player) = players.iter().find(|player| !player.supports_sides(sides)) {
+            return Err(UnsupportedSides { symbol: *player.get_symbol(), sides });
         }
+
+        Ok(Self {
+            players,
+            board,
+            display: true,
+            rng: StdRng::from_entropy(),
+            seed: None,
+            starting_rule: StartingRule::Random,
+            last_game: None
+        })
+    }
+
+    /// Sets whether the board is shown before every turn. The board is drawn in place if a
+    /// `TerminalGuard` is active, and printed otherwise.
+    pub fn set_display(&mut self, display: bool) {
+        self.display = display;
+    }
+
+    pub fn displays(&self) -> bool {
+        self.display
     }
 
-    fn get_player(&self, current: &CurrentPlayer) -> &Rc<Player> {
-        match current {
-            CurrentPlayer::Player1 => &self.player1,
-            CurrentPlayer::Player2 => &self.player2
+    /// Seeds the choice of who moves first, so that games with the same seed and reproducible
+    /// players play out the same way. The seed is kept in the game's record.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.seed = Some(seed);
+    }
+
+    /// Makes the random choices of the game with `rng`. Games played this way have no seed to record.
+    pub fn set_rng(&mut self, rng: StdRng) {
+        self.rng = rng;
+        self.seed = None;
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Sets who moves first in the games started by [`cycle`](#method.cycle). Players start at
+    /// random by default. Fails if the rule names a player past the last one.
+    pub fn set_starting_rule(&mut self, rule: StartingRule) -> Result<(), NoSuchPlayer> {
+        if let StartingRule::Player(index) = rule {
+            CurrentPlayer::new(index, self.players.len())?;
         }
+        self.starting_rule = rule;
+        Ok(())
     }
 
-    pub fn cycle(&mut self) -> Option<Winner> {
+    pub fn starting_rule(&self) -> StartingRule {
+        self.starting_rule
+    }
 
-        let mut places = 0;
+    /// Clears the board for another game between the same players. The starting rule decides who
+    /// moves first from how the last game went.
+    pub fn new_game(&mut self) {
+        self.board = Board::with_config(*self.board.config());
+    }
 
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
 
-        let mut player = random::<CurrentPlayer>();
+    pub fn player1(&self) -> &Arc<Player> {
+        self.player(CurrentPlayer::PLAYER1)
+    }
 
-        while places < 9 {
-            println!("{}", self.board);
-            let result = self.player_place(self.get_player(&player).clone(), &mut places);
-            if result.is_some() {
-                return result;
+    pub fn player2(&self) -> &Arc<Player> {
+        self.player(CurrentPlayer::PLAYER2)
+    }
+
+    /// Every player, in turn order
+    pub fn players(&self) -> &[Arc<Player>] {
+        &self.players
+    }
+
+    pub fn player(&self, current: CurrentPlayer) -> &Arc<Player> {
+        &self.players[current.0]
+    }
+
+    /// Continues a recorded game. The players are matched to the record by their symbols, and the
+    /// game keeps the record's seed.
+    pub fn resume(player1: Player, player2: Player, record: &GameRecord) -> Result<Self, RecordError> {
+        Self::resume_players(vec![player1, player2], record)
+    }
+
+    /// Continues a recorded game between any number of players, who take turns in the order the
+    /// record's symbols are listed in
+    ///
+    /// # Panics
+    /// If there are fewer than two players
+    pub fn resume_players(players: Vec<Player>, record: &GameRecord) -> Result<Self, RecordError> {
+        let mut players: Vec<Option<Player>> = players.into_iter().map(Some).collect();
+        let mut ordered = vec![];
+        for &symbol in record.symbols() {
+            let player = players.iter_mut()
+                .find(|player| player.as_ref().is_some_and(|player| *player.get_symbol() == symbol))
+                .and_then(Option::take)
+                .ok_or(RecordError::MissingPlayer(symbol))?;
+            ordered.push(player);
+        }
+        // Players who aren't in the record can't join the game
+        ordered.extend(players.into_iter().flatten());
+
+        let mut cycle = Self::with_board(ordered, Board::with_config(*record.config()))?;
+        cycle.board = record.replay(&cycle.players)?;
+        if let Some(seed) = record.seed() {
+            cycle.set_seed(seed);
+        }
+        Ok(cycle)
+    }
+
+    /// Records the game so far. An aborted game, or one without an outcome yet, is recorded as
+    /// unfinished so it can be resumed.
+    pub fn record(&self, outcome: Option<&GameOutcome>) -> GameRecord {
+        let result = match outcome {
+            Some(GameOutcome::Win { player, .. }) => RecordResult::Win(*player.get_symbol()),
+            Some(GameOutcome::Draw) => RecordResult::Draw,
+            Some(GameOutcome::Aborted { .. }) | None => RecordResult::Unfinished
+        };
+        let symbols = self.players.iter().map(|player| *player.get_symbol()).collect();
+        let mut record = GameRecord::from_board(&self.board, symbols, result);
+        let mut teams: Vec<(&Team, Vec<char>)> = vec![];
+        for player in &self.players {
+            if let Some(team) = player.team() {
+                match teams.iter_mut().find(|(other, _)| *other == team) {
+                    Some((_, symbols)) => symbols.push(*player.get_symbol()),
+                    None => teams.push((team, vec![*player.get_symbol()])),
+                }
             }
+        }
+        record.set_teams(teams.into_iter()
+            .map(|(team, symbols)| RecordedTeam { name: team.name().to_string(), symbols })
+            .collect());
+        record.set_seed(self.seed);
+        record
+    }
+
+    fn current_player_of(&self, player: &Arc<Player>) -> CurrentPlayer {
+        let index = self.players.iter()
+            .position(|other| other == player)
+            .expect("Every move on the board is played by one of the players");
+        CurrentPlayer(index)
+    }
 
-            places += 1;
-            Self::next_player(&mut player);
+    /// Plays the game until it ends, with the starting rule choosing who goes first
+    pub fn cycle(&mut self) -> GameOutcome {
+        let first = self.starting_rule.starter(self.last_game, self.players.len(), &mut self.rng);
+        self.cycle_from(first)
+    }
+
+    /// Plays a resumed game until it ends, starting with whoever is next to move. A random player
+    /// goes first if no moves have been played.
+    pub fn cycle_resumed(&mut self) -> GameOutcome {
+        if let Some(winner) = self.board.check_winner() {
+            return GameOutcome::win(winner, self.board.history().len(), &self.players);
+        }
+
+        let player = match self.player_after_last_move() {
+            Some(player) => player,
+            None => CurrentPlayer::random(self.players.len(), &mut self.rng)
+        };
+        self.cycle_from(player)
+    }
+
+    /// The player after whoever played the last move, or the player of the first move that
+    /// can be redone if every move was taken back
+    fn player_after_last_move(&self) -> Option<CurrentPlayer> {
+        match (self.board.last_move(), self.board.next_redo()) {
+            (Some(last_move), _) => Some(self.current_player_of(&last_move.player).next(self.players.len())),
+            (None, Some(first_move)) => Some(self.current_player_of(&first_move.player)),
+            (None, None) => None
+        }
+    }
+
+    /// Plays the game until it ends, starting with `player`
+    pub fn cycle_from(&mut self, player: CurrentPlayer) -> GameOutcome {
+        let outcome = self.play_from(player);
+
+        // Resumed games were started by whoever played the first move
+        let starter = match self.board.history().first() {
+            Some(first_move) => self.current_player_of(&first_move.player),
+            None => player
+        };
+        let winner = outcome.winner().map(|winner| self.current_player_of(winner));
+        self.last_game = Some(LastGame { starter, winner });
+        outcome
+    }
+
+    fn play_from(&mut self, mut player: CurrentPlayer) -> GameOutcome {
+        while !self.board.is_full() {
+            self.show(self.player(player));
+            match self.player_turn(self.player(player).clone()) {
+                Ok(Some(winner)) => {
+                    return GameOutcome::win(winner, self.board.history().len(), &self.players);
+                },
+                Ok(None) => {},
+                Err(reason) => {
+                    return GameOutcome::Aborted { reason };
+                }
+            }
+
+            player = self.player_after_last_move().unwrap_or(player);
         }
 
-        None
+        GameOutcome::Draw
+    }
+
+    fn show(&self, player: &Arc<Player>) {
+        if !self.display {
+            return;
+        }
 
+        // The starter is announced before the first move
+        let first_move = self.board.history().is_empty();
+        if render::is_active() {
+            let status = if first_move {
+                format!("{} moves first", player)
+            } else {
+                format!("{} to move", player)
+            };
+            render::draw(&self.board, &status, "", None).expect("Could not draw the board");
+        } else {
+            if first_move {
+                println!("{} moves first", player);
+            }
+            println!("{}", self.board);
+        }
     }
 
-    fn player_place(&mut self, player: Rc<Player>, places: &mut i32) -> Option<Winner> {
-        loop {
-            let player_move = player.next_move(&self.board);
+    /// Lets a player act until they place a move, or take back or replay moves. The player to move
+    /// next is always the one after whoever played the last move on the board.
+    fn player_turn(&mut self, player: Arc<Player>) -> Result<Option<Winner>, String> {
+        for _ in 0..MAX_ILLEGAL_MOVES {
+            match player.next_action(&self.board) {
+                Action::Place(player_move) => {
+                    if let Ok(winner) = self.board.make_move(player_move) {
+                        return Ok(winner);
+                    }
+                },
+                Action::Undo => {
+                    while let Some(undone) = self.board.undo() {
+                        if undone.player == player {
+                            break;
+                        }
+                    }
+                    return Ok(None);
+                },
+                Action::Redo => {
+                    while let Some(result) = self.board.redo() {
+                        let winner = result.map_err(|e| format!("Could not redo a move: {:?}", e))?;
+                        if winner.is_some() {
+                            return Ok(winner);
+                        }
 
-            match self.board.make_move(player_move) {
-                Ok(None) => {
-                    *places += 1;
-                    break;
+                        if self.board.next_redo().is_none_or(|next| next.player == player) {
+                            break;
+                        }
+                    }
+                    return Ok(None);
                 },
-                Ok(winner) => { return winner; },
-                Err(_) => {}
+                Action::Quit => {
+                    return Err(format!("{} quit the game", player));
+                }
+                Action::Abandon(reason) => {
+                    return Err(reason);
+                }
+            }
+        }
+
+        Err(format!("{} made {} illegal moves in a row", player, MAX_ILLEGAL_MOVES))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::Move;
+    use crate::game::player::{Controller, PlayerBuilder};
+    use crate::game::player::controllers::minimax::MinimaxController;
+    use crate::game::player::controllers::difficulty::{Difficulty, DifficultyController};
+    use crate::game::player::controllers::mcts::MctsController;
+    use crate::game::player::controllers::random::RandomController;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    struct AlwaysCorner;
+
+    enum Step {
+        At(u8, u8),
+        Undo,
+        Redo,
+    }
+
+    struct Scripted(Mutex<VecDeque<Step>>);
+
+    impl Scripted {
+        fn new(steps: Vec<Step>) -> Self {
+            Scripted(Mutex::new(steps.into()))
+        }
+    }
+
+    impl Controller for Scripted {
+        fn get_next_move(&self, _player: &Arc<Player>, _board: &Board) -> Move {
+            unreachable!()
+        }
+
+        fn get_next_action(&self, player: &Arc<Player>, _board: &Board) -> Action {
+            match self.0.lock().unwrap().pop_front().expect("Script ran out of steps") {
+                Step::At(x, y) => Action::Place(Move::new(x, y, player)),
+                Step::Undo => Action::Undo,
+                Step::Redo => Action::Redo,
+            }
+        }
+    }
+
+    impl Controller for AlwaysCorner {
+        fn get_next_move(&self, player: &Arc<Player>, _board: &Board) -> Move {
+            Move::new(0, 0, player)
+        }
+    }
+
+    #[test]
+    fn perfect_players_draw() {
+        let builder = PlayerBuilder::new();
+        let p1 = builder.new_player('x', MinimaxController::new().into()).unwrap();
+        let p2 = builder.new_player('o', MinimaxController::new().into()).unwrap();
+
+        let mut cycle = GameCycle::new(p1, p2);
+        match cycle.cycle() {
+            GameOutcome::Draw => {},
+            outcome => panic!("Expected a draw, got {:?}", outcome)
+        }
+        assert!(cycle.board().is_full());
+    }
+
+    #[test]
+    fn win_reports_line_and_move_number() {
+        let builder = PlayerBuilder::new();
+        let p1 = builder.new_player('x', MinimaxController::new().into()).unwrap();
+        let p2 = builder.new_player('o', MinimaxController::with_max_depth(1).into()).unwrap();
+
+        let mut cycle = GameCycle::with_config(p1, p2, BoardConfig::new(4, 1, 2).unwrap());
+        match cycle.cycle() {
+            GameOutcome::Win { player, winning_line, move_number, .. } => {
+                assert_eq!(winning_line.len(), 2);
+                assert_eq!(move_number, cycle.board().filled_positions());
+                for (x, y) in winning_line {
+                    assert_eq!(cycle.board().get_at_pos(x, y).unwrap().as_ref(), Some(&player));
+                }
+            },
+            outcome => panic!("Expected a win, got {:?}", outcome)
+        }
+    }
+
+    #[test]
+    fn repeated_illegal_moves_abort() {
+        let builder = PlayerBuilder::new();
+        let p1 = builder.new_player('x', AlwaysCorner.into()).unwrap();
+        let p2 = builder.new_player('o', AlwaysCorner.into()).unwrap();
+
+        let mut cycle = GameCycle::new(p1, p2);
+        match cycle.cycle() {
+            GameOutcome::Aborted { .. } => {},
+            outcome => panic!("Expected the game to be aborted, got {:?}", outcome)
+        }
+        assert_eq!(cycle.board().filled_positions(), 1);
+    }
+
+    #[test]
+    fn undo_takes_back_to_requesting_player() {
+        let builder = PlayerBuilder::new();
+        let x = builder.new_player('x', Scripted::new(vec![
+            Step::At(0, 0),
+            Step::Undo,
+            Step::Redo,
+            Step::At(1, 1),
+            Step::At(2, 2),
+        ]).into()).unwrap();
+        let o = builder.new_player('o', Scripted::new(vec![
+            Step::At(0, 1),
+            Step::At(0, 2),
+        ]).into()).unwrap();
+
+        let mut cycle = GameCycle::new(x, o);
+        match cycle.cycle_from(CurrentPlayer::PLAYER1) {
+            GameOutcome::Win { player, move_number, .. } => {
+                assert_eq!(player.get_symbol(), &'x');
+                assert_eq!(move_number, 5);
+            },
+            outcome => panic!("Expected x to win, got {:?}", outcome)
+        }
+        let history: Vec<_> = cycle.board().history().iter().map(|m| (m.x_pos, m.y_pos)).collect();
+        assert_eq!(history, vec![(0, 0), (0, 1), (1, 1), (0, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn undoing_the_first_move_returns_the_turn() {
+        let builder = PlayerBuilder::new();
+        let x = builder.new_player('x', Scripted::new(vec![
+            Step::At(0, 0),
+            Step::At(1, 0),
+            Step::At(2, 0),
+            Step::At(0, 0),
+        ]).into()).unwrap();
+        let o = builder.new_player('o', Scripted::new(vec![
+            Step::Undo,
+            Step::At(0, 1),
+            Step::At(1, 1),
+        ]).into()).unwrap();
+
+        let mut cycle = GameCycle::new(x, o);
+        match cycle.cycle_from(CurrentPlayer::PLAYER1) {
+            GameOutcome::Win { player, .. } => assert_eq!(player.get_symbol(), &'x'),
+            outcome => panic!("Expected x to win, got {:?}", outcome)
+        }
+        assert_eq!(cycle.board().history().len(), 5);
+    }
+
+    #[test]
+    fn saved_games_resume_with_the_next_player() {
+        let builder = PlayerBuilder::new();
+        let x = builder.new_player('x', Scripted::new(vec![Step::At(0, 0)]).into()).unwrap();
+        let o = builder.new_player('o', AlwaysCorner.into()).unwrap();
+
+        // o can only play the corner x already took, so the game is abandoned after one move
+        let mut cycle = GameCycle::new(x, o);
+        let outcome = cycle.cycle_from(CurrentPlayer::PLAYER1);
+        let record = cycle.record(Some(&outcome));
+        assert_eq!(record.result(), RecordResult::Unfinished);
+        let saved: GameRecord = record.to_string().parse().unwrap();
+
+        let builder = PlayerBuilder::new();
+        let o = builder.new_player('o', Scripted::new(vec![Step::At(1, 0), Step::At(1, 1)]).into()).unwrap();
+        let x = builder.new_player('x', Scripted::new(vec![Step::At(0, 1), Step::At(0, 2)]).into()).unwrap();
+        let mut resumed = GameCycle::resume(o, x, &saved).unwrap();
+        match resumed.cycle_resumed() {
+            GameOutcome::Win { player, move_number, .. } => {
+                assert_eq!(player.get_symbol(), &'x');
+                assert_eq!(move_number, 5);
+            },
+            outcome => panic!("Expected x to win, got {:?}", outcome)
+        }
+    }
+
+    #[test]
+    fn seeded_games_repeat() {
+        let play = |seed: u64| {
+            let builder = PlayerBuilder::with_seed(seed);
+            let x = builder.new_player('x', RandomController::with_seed(builder.next_seed()).into()).unwrap();
+            let o = builder.new_player('o', RandomController::with_seed(builder.next_seed()).into()).unwrap();
+            let mut cycle = GameCycle::new(x, o);
+            cycle.set_display(false);
+            cycle.set_seed(seed);
+            cycle.cycle();
+            cycle.record(None).to_string()
+        };
+
+        assert_eq!(play(11), play(11));
+        assert!((0..10).any(|seed| play(seed) != play(11)));
+    }
+
+    /// Plays a game between a casual AI and a short MCTS search, with every random choice drawn
+    /// from `seed`
+    fn seeded_ai_game(seed: u64) -> GameRecord {
+        let builder = PlayerBuilder::with_seed(seed);
+        let casual = DifficultyController::new(Difficulty::Casual).with_seed(builder.next_seed());
+        let mcts = MctsController::with_iterations(200).with_seed(builder.next_seed());
+        let x = builder.new_player('x', casual.into()).unwrap();
+        let o = builder.new_player('o', mcts.into()).unwrap();
+
+        let mut cycle = GameCycle::new(x, o);
+        cycle.set_display(false);
+        cycle.set_seed(seed);
+        let outcome = cycle.cycle();
+        cycle.record(Some(&outcome))
+    }
+
+    #[test]
+    fn seeded_ai_games_replay_move_for_move() {
+        for seed in 0..5 {
+            let saved: GameRecord = seeded_ai_game(seed).to_string().parse().unwrap();
+            assert_eq!(saved.seed(), Some(seed));
+
+            let replayed = seeded_ai_game(saved.seed().unwrap());
+            assert_eq!(replayed.moves().len(), saved.moves().len());
+            for (number, (played, recorded)) in replayed.moves().iter().zip(saved.moves()).enumerate() {
+                assert_eq!(played, recorded, "Move {} of the game with seed {} differs", number + 1, seed);
             }
+            assert_eq!(replayed.result(), saved.result());
+        }
+    }
+
+    #[test]
+    fn resumed_games_keep_their_seed() {
+        let record = seeded_ai_game(3);
+        let builder = PlayerBuilder::new();
+        let x = builder.new_player('x', AlwaysCorner.into()).unwrap();
+        let o = builder.new_player('o', AlwaysCorner.into()).unwrap();
+        let resumed = GameCycle::resume(x, o, &record).unwrap();
+        assert_eq!(resumed.seed(), Some(3));
+        assert_eq!(resumed.record(None).seed(), Some(3));
+    }
+
+    #[test]
+    fn starting_rules_follow_the_last_game() {
+        const PLAYER1: CurrentPlayer = CurrentPlayer::PLAYER1;
+        const PLAYER2: CurrentPlayer = CurrentPlayer::PLAYER2;
+        let player3 = CurrentPlayer::new(2, 3).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+        let last = |starter, winner| Some(LastGame { starter, winner });
+
+        assert_eq!(StartingRule::Player(1).starter(last(PLAYER2, None), 2, &mut rng), PLAYER2);
+        assert_eq!(StartingRule::Player(2).starter(None, 3, &mut rng), player3);
+        assert_eq!(StartingRule::Alternate.starter(last(PLAYER2, Some(PLAYER1)), 2, &mut rng), PLAYER1);
+        assert_eq!(StartingRule::LoserStarts.starter(last(PLAYER2, Some(PLAYER1)), 2, &mut rng), PLAYER2);
+        assert_eq!(StartingRule::LoserStarts.starter(last(PLAYER2, None), 2, &mut rng), PLAYER1);
+
+        // With three players, starting passes around all of them
+        assert_eq!(StartingRule::Alternate.starter(last(PLAYER2, None), 3, &mut rng), player3);
+        assert_eq!(StartingRule::Alternate.starter(last(player3, None), 3, &mut rng), PLAYER1);
+        assert_eq!(StartingRule::LoserStarts.starter(last(PLAYER1, Some(player3)), 3, &mut rng), PLAYER1);
+
+        // Without a last game, the series starts with a random player
+        let starters: Vec<CurrentPlayer> = (0..30).map(|_| StartingRule::LoserStarts.starter(None, 3, &mut rng)).collect();
+        assert!(starters.contains(&PLAYER1) && starters.contains(&PLAYER2) && starters.contains(&player3));
+
+        for rule in &StartingRule::ALL {
+            assert_eq!(rule.to_string().parse(), Ok(*rule));
+        }
+        assert_eq!("player3".parse(), Ok(StartingRule::Player(2)));
+        assert!("0".parse::<StartingRule>().is_err());
+        assert_eq!(CurrentPlayer::new(3, 3), Err(NoSuchPlayer { index: 3, players: 3 }));
+    }
+
+    #[test]
+    fn alternating_starters_across_games() {
+        let builder = PlayerBuilder::new();
+        let x = builder.new_player('x', MinimaxController::new().into()).unwrap();
+        let o = builder.new_player('o', MinimaxController::new().into()).unwrap();
+        let mut cycle = GameCycle::new(x, o);
+        cycle.set_display(false);
+        cycle.set_starting_rule(StartingRule::Alternate).unwrap();
+        assert_eq!(cycle.set_starting_rule(StartingRule::Player(2)), Err(NoSuchPlayer { index: 2, players: 2 }));
+
+        let mut starters = vec![];
+        for _ in 0..4 {
+            cycle.new_game();
+            cycle.cycle();
+            starters.push(*cycle.board().history()[0].player.get_symbol());
+        }
+        assert_ne!(starters[0], starters[1]);
+        assert_eq!(starters[0], starters[2]);
+        assert_eq!(starters[1], starters[3]);
+    }
+
+    #[test]
+    fn three_players_take_turns() {
+        let builder = PlayerBuilder::new();
+        let x = builder.new_player('x', Scripted::new(vec![
+            Step::At(0, 0), Step::At(1, 0), Step::At(5, 5), Step::At(5, 4),
+        ]).into()).unwrap();
+        let o = builder.new_player('o', Scripted::new(vec![
+            Step::At(0, 1), Step::At(1, 1), Step::At(2, 1), Step::At(4, 4),
+        ]).into()).unwrap();
+        let plus = builder.new_player('+', Scripted::new(vec![
+            Step::At(2, 0), Step::At(0, 2), Step::At(1, 2), Step::At(2, 2), Step::At(3, 2),
+        ]).into()).unwrap();
+
+        // + moves first, blocks x's row and completes its own
+        let mut cycle = GameCycle::with_players(vec![x, o, plus], BoardConfig::square(6, 4).unwrap()).unwrap();
+        cycle.set_display(false);
+        let outcome = cycle.cycle_from(CurrentPlayer::new(2, 3).unwrap());
+        match &outcome {
+            GameOutcome::Win { player, winning_line, move_number, .. } => {
+                assert_eq!(player.get_symbol(), &'+');
+                assert_eq!(winning_line.len(), 4);
+                assert!(winning_line.iter().all(|&(_, y)| y == 2));
+                assert_eq!(*move_number, 13);
+            },
+            outcome => panic!("Expected + to win, got {:?}", outcome)
+        }
+
+        let turns: Vec<char> = cycle.board().history().iter().map(|played| *played.player.get_symbol()).collect();
+        assert_eq!(turns.iter().collect::<String>(), "+xo+xo+xo+xo+");
+
+        // The record lists every player, and resumes with all of them in the same order
+        let record = cycle.record(Some(&outcome));
+        assert_eq!(record.symbols(), &['x', 'o', '+']);
+        let builder = PlayerBuilder::new();
+        let players = ['+', 'o', 'x'].iter()
+            .map(|&symbol| builder.new_player(symbol, AlwaysCorner.into()).unwrap())
+            .collect();
+        let resumed = GameCycle::resume_players(players, &record).unwrap();
+        let symbols: Vec<char> = resumed.players().iter().map(|player| *player.get_symbol()).collect();
+        assert_eq!(symbols, vec!['x', 'o', '+']);
+        assert_eq!(resumed.board().history().len(), 13);
+    }
+
+    #[test]
+    fn teams_win_with_mixed_lines() {
+        let builder = PlayerBuilder::new();
+        let red = builder.new_team("red").unwrap();
+        let blue = builder.new_team("blue").unwrap();
+        let player = |symbol, team, steps| {
+            let mut player = builder.new_player(symbol, Scripted::new(steps).into()).unwrap();
+            builder.assign_team(&mut player, team);
+            player
         };
-        None
+        let x = player('x', &red, vec![Step::At(0, 0), Step::At(2, 0)]);
+        let plus = player('+', &red, vec![Step::At(1, 0)]);
+        let o = player('o', &blue, vec![Step::At(0, 1)]);
+        let hash = player('#', &blue, vec![Step::At(3, 3)]);
+
+        // Turns alternate between the teams
+        let mut cycle = GameCycle::with_teams(vec![x, plus, o, hash], BoardConfig::square(4, 3).unwrap()).unwrap();
+        cycle.set_display(false);
+        let symbols: Vec<char> = cycle.players().iter().map(|player| *player.get_symbol()).collect();
+        assert_eq!(symbols, vec!['x', 'o', '+', '#']);
+
+        let outcome = cycle.cycle_from(CurrentPlayer::PLAYER1);
+        let team = outcome.winning_team().expect("Expected red to win");
+        assert_eq!(team.team, red);
+        let members: Vec<char> = team.members.iter().map(|player| *player.get_symbol()).collect();
+        assert_eq!(members, vec!['x', '+']);
+        assert_eq!(outcome.to_string(), "Team red (Player x, Player +) wins on move 5 with the line (0, 0) (1, 0) (2, 0)");
+    }
+
+    #[test]
+    fn teams_must_be_even() {
+        let builder = PlayerBuilder::new();
+        let red = builder.new_team("red").unwrap();
+        let blue = builder.new_team("blue").unwrap();
+        let players = |teams: &[Option<&Team>]| -> Vec<Player> {
+            teams.iter()
+                .zip(&['x', 'o', '+', '#'])
+                .map(|(team, &symbol)| {
+                    let mut player = PlayerBuilder::new().new_player(symbol, AlwaysCorner.into()).unwrap();
+                    if let Some(team) = team {
+                        builder.assign_team(&mut player, team);
+                    }
+                    player
+                })
+                .collect()
+        };
+        let with_teams = |teams: &[Option<&Team>]| GameCycle::with_teams(players(teams), BoardConfig::default()).err();
+
+        assert_eq!(with_teams(&[Some(&red), Some(&blue), Some(&red)]), Some(TeamError::UnevenTeams));
+        assert_eq!(with_teams(&[Some(&red), Some(&red)]), Some(TeamError::TooFewTeams));
+        assert_eq!(with_teams(&[Some(&red), None]), Some(TeamError::Unassigned('o')));
+        assert_eq!(with_teams(&[Some(&red), Some(&blue)]), None);
+    }
+
+    #[test]
+    fn searches_only_play_two_sides() {
+        let builder = PlayerBuilder::new();
+        let red = builder.new_team("red").unwrap();
+        let blue = builder.new_team("blue").unwrap();
+        let player = |symbol, controller: Box<dyn Controller>, team: Option<&Team>| {
+            let mut player = PlayerBuilder::new().new_player(symbol, controller).unwrap();
+            if let Some(team) = team {
+                builder.assign_team(&mut player, team);
+            }
+            player
+        };
+        let config = BoardConfig::square(6, 4).unwrap();
+
+        // A minimax player would treat both of the others as a single opponent
+        let three = vec![
+            player('x', RandomController::new().into(), None),
+            player('o', MinimaxController::new().into(), None),
+            player('+', RandomController::new().into(), None),
+        ];
+        let error = GameCycle::with_players(three, config).err();
+        assert_eq!(error, Some(UnsupportedSides { symbol: 'o', sides: 3 }));
+        assert_eq!(error.unwrap().to_string(), "The player with the symbol 'o' can only play games between two sides, not 3");
+
+        let three = vec![
+            player('x', RandomController::new().into(), None),
+            player('o', RandomController::new().into(), None),
+            player('+', MctsController::with_iterations(100).into(), None),
+        ];
+        assert_eq!(GameCycle::with_players(three, config).err(), Some(UnsupportedSides { symbol: '+', sides: 3 }));
+
+        // Two teams are two sides, whoever plays for them
+        let teams = vec![
+            player('x', DifficultyController::new(Difficulty::Hard).into(), Some(&red)),
+            player('o', MinimaxController::new().into(), Some(&blue)),
+            player('+', RandomController::new().into(), Some(&red)),
+            player('#', MctsController::with_iterations(100).into(), Some(&blue)),
+        ];
+        let mut cycle = GameCycle::with_teams(teams, BoardConfig::square(4, 3).unwrap()).unwrap();
+        cycle.set_display(false);
+        assert!(matches!(cycle.cycle_from(CurrentPlayer::PLAYER1), GameOutcome::Win { .. } | GameOutcome::Draw));
+
+        let three_teams = vec![
+            player('x', MinimaxController::new().into(), Some(&red)),
+            player('o', RandomController::new().into(), Some(&blue)),
+            player('+', RandomController::new().into(), Some(&builder.new_team("green").unwrap())),
+        ];
+        assert_eq!(
+            GameCycle::with_teams(three_teams, config).err(),
+            Some(TeamError::UnsupportedSides(UnsupportedSides { symbol: 'x', sides: 3 }))
+        );
     }
 }
@@ -1,58 +1,145 @@
-use crate::game::Move;
+use crate::game::{Action, Move};
 use crate::game::board::Board;
+use crate::game::profile::{Color, Profile};
 
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 use std::collections::HashSet;
 use std::fmt::{Debug, Display};
-use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::cell::RefCell;
 
 pub mod controllers;
-pub trait Controller {
-    fn get_next_move(&self, player: &Rc<Player>, board: &Board) -> Move;
+pub trait Controller: Send + Sync {
+    fn get_next_move(&self, player: &Arc<Player>, board: &Board) -> Move;
+
+    fn get_next_action(&self, player: &Arc<Player>, board: &Board) -> Action {
+        Action::Place(self.get_next_move(player, board))
+    }
+
+    /// Whether the controller can play a game between `sides` sides, where every team and every
+    /// player without a team is a side
+    fn supports_sides(&self, _sides: usize) -> bool {
+        true
+    }
 }
 
 pub trait SelfController {
     fn next_move(&self, board: &Board) -> Move;
+
+    fn next_action(&self, board: &Board) -> Action;
 }
 
 
 pub struct Player {
     id: i32,
     symbol: char,
+    name: Option<String>,
+    display_name: Option<String>,
+    color: Option<Color>,
+    team: Option<Team>,
     controller: Box<dyn Controller>,
 }
 
-impl Player {
-    fn new(id: i32, symbol: char, controller: Box<dyn Controller>) -> Self {
+/// Team ids are handed out from here rather than by each builder, so teams from different
+/// builders never compare equal
+static NEXT_TEAM_ID: AtomicU32 = AtomicU32::new(0);
+
+/// A side that several players play for together. Lines made of any of its players' marks win
+/// for the whole team.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Team {
+    id: u32,
+    name: String,
+}
 
+impl Team {
+    /// A number that is unique among all the teams created, whichever builder created them
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Display for Team {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        write!(f, "Team {}", self.name)
+    }
+}
+
+impl Player {
+    fn new(id: i32, symbol: char, name: Option<String>, controller: Box<dyn Controller>) -> Self {
         Player {
             id,
             symbol,
+            name,
+            display_name: None,
+            color: None,
+            team: None,
             controller,
         }
     }
 
-
     pub fn get_symbol(&self) -> &char {
         &self.symbol
     }
 
+    /// A number that is unique among the players created by the same builder
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    /// The name the player is known by across games, such as for ratings. Unlike the id, it
+    /// stays the same every time the player is created.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The color the player's marks are drawn in, if they have one
+    pub fn color(&self) -> Option<Color> {
+        self.color
+    }
 
+    pub fn team(&self) -> Option<&Team> {
+        self.team.as_ref()
+    }
 
+    /// Whether the players' marks count towards the same lines, which they do for the same
+    /// player and for players on the same team
+    pub fn is_teammate(&self, other: &Player) -> bool {
+        self == other || (self.team.is_some() && self.team == other.team)
+    }
 }
 
-impl SelfController for Rc<Player>  {
+impl SelfController for Arc<Player>  {
 
     fn next_move(&self, board: &Board) -> Move  {
         Controller::get_next_move(&* self.controller, self, board)
     }
+
+    fn next_action(&self, board: &Board) -> Action {
+        Controller::get_next_action(&* self.controller, self, board)
+    }
 }
 
 
-impl Controller for Rc<Player> {
-    fn get_next_move(&self, player: &Rc<Player>, board: &Board) -> Move {
+impl Controller for Arc<Player> {
+    fn get_next_move(&self, player: &Arc<Player>, board: &Board) -> Move {
         self.controller.get_next_move(player, board)
     }
+
+    fn get_next_action(&self, player: &Arc<Player>, board: &Board) -> Action {
+        self.controller.get_next_action(player, board)
+    }
+
+    fn supports_sides(&self, sides: usize) -> bool {
+        self.controller.supports_sides(sides)
+    }
 }
 
 impl Debug for Player {
@@ -64,7 +151,10 @@ impl Debug for Player {
 
 impl Display for Player {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        write!(f, "Player {}", self.symbol)
+        match self.display_name.as_ref().or(self.name.as_ref()) {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "Player {}", self.symbol)
+        }
     }
 }
 
@@ -74,24 +164,175 @@ impl PartialEq for Player {
     }
 }
 
+/// Players are serialized as their id, symbol, names, color and team. A deserialized player keeps the id, so
+/// it is equal to the player it was created from, but it has no controller: asked for an action, it abandons
+/// the game.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PlayerRef {
+    id: i32,
+    symbol: char,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<Color>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    team: Option<Team>,
+}
+
+#[cfg(feature = "serde")]
+struct Detached;
+
+#[cfg(feature = "serde")]
+impl Controller for Detached {
+    fn get_next_move(&self, player: &Arc<Player>, _board: &Board) -> Move {
+        panic!("{} was deserialized and has no controller to choose moves", player)
+    }
+
+    fn get_next_action(&self, player: &Arc<Player>, _board: &Board) -> Action {
+        Action::Abandon(format!("{} was deserialized and has no controller to choose moves", player))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Player {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PlayerRef {
+            id: self.id,
+            symbol: self.symbol,
+            name: self.name.clone(),
+            display_name: self.display_name.clone(),
+            color: self.color,
+            team: self.team.clone(),
+        }.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Player {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let PlayerRef { id, symbol, name, display_name, color, team } = PlayerRef::deserialize(deserializer)?;
+        let mut player = Player::new(id, symbol, name, Box::new(Detached));
+        player.display_name = display_name;
+        player.color = color;
+        player.team = team;
+        Ok(player)
+    }
+}
+
+/// Creates players with unique symbols and ids. Ids are drawn at random, from a seeded generator
+/// if the builder was created with a seed, so that the same players are created every time.
 pub struct PlayerBuilder {
     used_ids: RefCell<HashSet<i32>>,
-    used_symbols: RefCell<HashSet<char>>
+    used_symbols: RefCell<HashSet<char>>,
+    teams: RefCell<Vec<String>>,
+    rng: RefCell<StdRng>
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct SymbolUsed(char);
 
+impl Display for SymbolUsed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        write!(f, "The symbol '{}' is already used by another player", self.0)
+    }
+}
+
+impl std::error::Error for SymbolUsed {}
+
+/// Why a builder couldn't create a team
+#[derive(Debug, PartialEq, Eq)]
+pub enum TeamNameError {
+    Empty,
+    Used(String),
+}
+
+impl Display for TeamNameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        match self {
+            TeamNameError::Empty => write!(f, "Teams need a name"),
+            TeamNameError::Used(name) => write!(f, "There is already a team named \"{}\"", name),
+        }
+    }
+}
+
+impl std::error::Error for TeamNameError {}
+
+impl Default for PlayerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PlayerBuilder {
 
     pub fn new() -> Self {
+        Self::with_rng(StdRng::from_entropy())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    /// Creates a builder that draws ids and seeds from `rng`
+    pub fn with_rng(rng: StdRng) -> Self {
         Self {
             used_ids: RefCell::new(HashSet::new()),
-            used_symbols: RefCell::new(HashSet::new())
+            used_symbols: RefCell::new(HashSet::new()),
+            teams: RefCell::new(vec![]),
+            rng: RefCell::new(rng)
         }
     }
 
+    /// Draws a seed from the builder's generator, for controllers that should be as reproducible
+    /// as the players themselves
+    pub fn next_seed(&self) -> u64 {
+        self.rng.borrow_mut().gen()
+    }
+
     pub fn new_player(&self, symbol: char, controller: Box<dyn Controller>) -> Result<Player, SymbolUsed> {
+        self.create_player(symbol, None, controller)
+    }
+
+    /// Creates a player with a stable name, which ratings and other records across games are kept under
+    pub fn new_named_player(&self, name: &str, symbol: char, controller: Box<dyn Controller>) -> Result<Player, SymbolUsed> {
+        self.create_player(symbol, Some(name.to_string()), controller)
+    }
+
+    /// Creates a player from a profile, playing with its preferred symbol
+    pub fn new_profile_player(&self, profile: &Profile, controller: Box<dyn Controller>) -> Result<Player, SymbolUsed> {
+        let mut player = self.create_player(profile.symbol, Some(profile.name.clone()), controller)?;
+        player.display_name = Some(profile.display_name.clone());
+        player.color = profile.color;
+        Ok(player)
+    }
+
+    /// Creates a team for players to be assigned to. Names can't be empty, or be used by another
+    /// team of the same builder.
+    pub fn new_team(&self, name: &str) -> Result<Team, TeamNameError> {
+        if name.is_empty() {
+            return Err(TeamNameError::Empty);
+        }
+        let mut teams = self.teams.borrow_mut();
+        if teams.iter().any(|team| team == name) {
+            return Err(TeamNameError::Used(name.to_string()));
+        }
+
+        teams.push(name.to_string());
+        Ok(Team {
+            id: NEXT_TEAM_ID.fetch_add(1, Ordering::Relaxed),
+            name: name.to_string(),
+        })
+    }
+
+    /// Puts a player on a team, taking them off the team they were on
+    pub fn assign_team(&self, player: &mut Player, team: &Team) {
+        player.team = Some(team.clone());
+    }
+
+    fn create_player(&self, symbol: char, name: Option<String>, controller: Box<dyn Controller>) -> Result<Player, SymbolUsed> {
         {
             if self.used_symbols.borrow().contains(&symbol) {
                 return Err(SymbolUsed(symbol));
@@ -99,7 +340,7 @@ impl PlayerBuilder {
         }
 
         let id = loop {
-            let id_attempt: i32 = rand::random();
+            let id_attempt: i32 = self.rng.borrow_mut().gen();
 
             if !self.used_ids.borrow().contains(&id_attempt) {
                 break id_attempt;
@@ -111,7 +352,7 @@ impl PlayerBuilder {
         self.used_ids.borrow_mut().insert(id);
         self.used_symbols.borrow_mut().insert(symbol);
 
-        Ok(Player::new(id, symbol, controller))
+        Ok(Player::new(id, symbol, name, controller))
     }
 }
 
@@ -134,4 +375,57 @@ mod test {
         let result = builder.new_player('c', Box::new(HumanController));
         assert_eq!(result, Err(SymbolUsed('c')));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn seeded_builders_repeat_their_ids() {
+        let ids = |seed| {
+            let builder = PlayerBuilder::with_seed(seed);
+            let first = builder.new_player('x', Box::new(HumanController)).unwrap().id();
+            let second = builder.new_player('o', Box::new(HumanController)).unwrap().id();
+            (first, second, builder.next_seed())
+        };
+        assert_eq!(ids(3), ids(3));
+        assert_ne!(ids(3), ids(4));
+    }
+
+    #[test]
+    fn profile_players_are_shown_by_name() {
+        let builder = PlayerBuilder::new();
+        let profile = Profile::new("alice", 'a').with_display_name("Alice").with_color(Color::Red);
+        let alice = builder.new_profile_player(&profile, Box::new(HumanController)).unwrap();
+        assert_eq!((alice.to_string(), alice.name(), *alice.get_symbol()), ("Alice".to_string(), Some("alice"), 'a'));
+        assert_eq!(alice.color(), Some(Color::Red));
+
+        let bob = builder.new_named_player("bob", 'b', Box::new(HumanController)).unwrap();
+        assert_eq!(bob.to_string(), "bob");
+        assert_eq!(builder.new_player('c', Box::new(HumanController)).unwrap().to_string(), "Player c");
+        assert_eq!(builder.new_profile_player(&profile, Box::new(HumanController)), Err(SymbolUsed('a')));
+    }
+
+    #[test]
+    fn teammates_share_their_team() {
+        let builder = PlayerBuilder::new();
+        let red = builder.new_team("red").unwrap();
+        let blue = builder.new_team("blue").unwrap();
+        assert_ne!(red, blue);
+        assert_eq!(builder.new_team("red"), Err(TeamNameError::Used("red".to_string())));
+        assert_eq!(builder.new_team(""), Err(TeamNameError::Empty));
+
+        // Another builder's team of the same name is a different team
+        assert_ne!(PlayerBuilder::new().new_team("red").unwrap(), red);
+
+        let mut players: Vec<Player> = ['x', 'o', '+', '#'].iter()
+            .map(|&symbol| builder.new_player(symbol, Box::new(HumanController)).unwrap())
+            .collect();
+        assert!(!players[0].is_teammate(&players[2]));
+        assert!(players[0].is_teammate(&players[0]));
+
+        builder.assign_team(&mut players[0], &red);
+        builder.assign_team(&mut players[1], &blue);
+        builder.assign_team(&mut players[2], &red);
+        assert!(players[0].is_teammate(&players[2]));
+        assert!(!players[0].is_teammate(&players[1]));
+        assert!(!players[0].is_teammate(&players[3]));
+        assert_eq!(players[2].team().map(Team::name), Some("red"));
+    }
+}
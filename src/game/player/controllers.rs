@@ -1,21 +1,29 @@
 use crate::game::board::Board;
 use crate::game::player::{Controller, Player};
-use crate::game::Move;
+use crate::game::{render, Action, Move};
+use crate::game::render::Layout;
 
 
 use std::cmp::Ordering;
-use std::rc::Rc;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::str::FromStr;
 use std::time::Duration;
 
 
 use crossterm::{execute};
 use crossterm::terminal::{Clear, ClearType};
 
-use std::io::{stdout, Write};
+use std::io::stdout;
 use crossterm::style::Print;
 use crossterm::cursor::MoveToColumn;
-use crossterm::event::{Event, KeyCode};
+use crossterm::event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind};
 
+pub mod difficulty;
+pub mod minimax;
+pub mod mcts;
+pub mod random;
+pub mod transposition;
 
 pub struct HumanController;
 
@@ -94,18 +102,35 @@ impl FindNearPosition for Vec<(u8, u8)> {
 }
 
 impl Controller for HumanController {
-    fn get_next_move(&self, player: &Rc<Player>, board: &Board) -> Move
+    fn get_next_move(&self, player: &Arc<Player>, board: &Board) -> Move {
+        loop {
+            if let Action::Place(next_move) = self.get_next_action(player, board) {
+                return next_move;
+            }
+        }
+    }
+
+    /// Arrow keys move between open positions and enter places a mark there. While the board is
+    /// drawn full-screen, hovering over an open position moves there and clicking places a mark.
+    /// `u` requests an undo, `r` requests a redo, and escape or ctrl-c quits the game.
+    fn get_next_action(&self, player: &Arc<Player>, board: &Board) -> Action
     {
         let positions = board.get_open_positions();
         let mut position: &(u8, u8) = &positions[0];
 
         let write_line = |(x, y): &(u8, u8)| {
+            if render::is_active() {
+                let status = format!("{} to move", player);
+                let controls = "Arrow keys/mouse: move   Enter/click: place   u: undo   r: redo   Esc: quit";
+                render::draw(board, &status, controls, Some((*x, *y))).expect("Could not draw the board");
+                return;
+            }
+
             let mut stdout = stdout();
             let clear = Clear(ClearType::CurrentLine);
             let mov = MoveToColumn(0);
-            let print = Print(format!("[{symbol}] - Playing at {x}, {y}", symbol = player, x = x, y = y));
+            let print = Print(format!("[{symbol}] - Playing at {x}, {y} (u: undo, r: redo, esc: quit)", symbol = player, x = x, y = y));
             execute!(stdout, clear, mov, print).unwrap();
-            print!("[{symbol}] - Playing at {x}, {y}", symbol = player, x = x, y = y);
         };
 
         write_line(position);
@@ -115,16 +140,36 @@ impl Controller for HumanController {
 
 
             let event = crossterm::event::read();
-            let mut key = None;
+            let key;
             if let Ok(event) = event {
                 match event {
                     Event::Key(key_event) => {
+                        if key_event.modifiers.contains(KeyModifiers::CONTROL) && key_event.code == KeyCode::Char('c') {
+                            return Action::Quit;
+                        }
                         key = Some(key_event.code);
                     },
-                    Event::Mouse(_) => {
+                    Event::Mouse(mouse_event) => {
+                        let layout = Layout::new(*board.config());
+                        let hovered = layout.screen_to_cell(mouse_event.column, mouse_event.row)
+                            .and_then(|cell| positions.iter().find(|open| **open == cell));
+
+                        match (mouse_event.kind, hovered) {
+                            (MouseEventKind::Down(MouseButton::Left), Some(cell)) => {
+                                position = cell;
+                                break;
+                            },
+                            (MouseEventKind::Moved, Some(cell)) | (MouseEventKind::Drag(_), Some(cell))
+                                if cell != position => {
+                                position = cell;
+                                write_line(position);
+                            },
+                            _ => {}
+                        }
                         continue;
                     },
                     Event::Resize(_, _) => {
+                        write_line(position);
                         continue;
                     },
                 }
@@ -136,19 +181,28 @@ impl Controller for HumanController {
             let mut selected = false;
 
             if key == Some(KeyCode::Up) {
-                position = positions.nearest_position(&position, Direction::Up);
+                position = positions.nearest_position(position, Direction::Up);
                 position_updated = true;
             } else if key == Some(KeyCode::Right) {
-                position = positions.nearest_position(&position, Direction::Right);
+                position = positions.nearest_position(position, Direction::Right);
                 position_updated = true;
             } else if key == Some(KeyCode::Down) {
-                position = positions.nearest_position(&position, Direction::Down);
+                position = positions.nearest_position(position, Direction::Down);
                 position_updated = true;
             } else if key == Some(KeyCode::Left) {
-                position = positions.nearest_position(&position, Direction::Left);
+                position = positions.nearest_position(position, Direction::Left);
                 position_updated = true;
             } else if key == Some(KeyCode::Enter) {
                 selected = true;
+            } else if key == Some(KeyCode::Char('u')) {
+                println!();
+                return Action::Undo;
+            } else if key == Some(KeyCode::Char('r')) {
+                println!();
+                return Action::Redo;
+            } else if key == Some(KeyCode::Esc) {
+                println!();
+                return Action::Quit;
             }
 
             if position_updated && !selected {
@@ -167,7 +221,7 @@ impl Controller for HumanController {
 
         println!();
         let (x, y) = position;
-        Move::new(*x, *y, player)
+        Action::Place(Move::new(*x, *y, player))
 
     }
 }
@@ -178,6 +232,135 @@ impl <'a, T : 'a + Controller> From<T> for Box<dyn 'a + Controller> {
     }
 }
 
+/// A description of a controller that can be parsed from text, such as `minimax` or `mcts:5000`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControllerKind {
+    Human,
+    /// Uniformly random moves, from a seeded generator if there is a seed
+    Random {
+        seed: Option<u64>
+    },
+    Minimax {
+        max_depth: Option<u32>
+    },
+    Mcts {
+        iterations: u32
+    },
+    /// A search that plays as well as the difficulty allows, optionally with its own blunder rate
+    /// in percent
+    Ai {
+        difficulty: difficulty::Difficulty,
+        blunder_percent: Option<u32>
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownController(String);
+
+impl ControllerKind {
+    pub fn build(&self) -> Box<dyn Controller> {
+        self.build_with(None)
+    }
+
+    /// Builds the controller with its random choices seeded by `seed`, unless it was given a seed
+    /// of its own. Controllers without random choices ignore the seed.
+    pub fn build_seeded(&self, seed: u64) -> Box<dyn Controller> {
+        self.build_with(Some(seed))
+    }
+
+    fn build_with(&self, default_seed: Option<u64>) -> Box<dyn Controller> {
+        match *self {
+            ControllerKind::Human => HumanController.into(),
+            ControllerKind::Random { seed } => match seed.or(default_seed) {
+                Some(seed) => random::RandomController::with_seed(seed).into(),
+                None => random::RandomController::new().into()
+            },
+            ControllerKind::Minimax { max_depth: None } => minimax::MinimaxController::new().into(),
+            ControllerKind::Minimax { max_depth: Some(depth) } => minimax::MinimaxController::with_max_depth(depth).into(),
+            ControllerKind::Mcts { iterations } => {
+                let controller = mcts::MctsController::with_iterations(iterations);
+                match default_seed {
+                    Some(seed) => controller.with_seed(seed).into(),
+                    None => controller.into()
+                }
+            },
+            ControllerKind::Ai { difficulty, blunder_percent } => {
+                let mut settings = difficulty.settings();
+                if let Some(percent) = blunder_percent {
+                    settings.blunder_rate = percent.min(100) as f64 / 100.0;
+                }
+                let controller = difficulty::DifficultyController::with_settings(settings);
+                match default_seed {
+                    Some(seed) => controller.with_seed(seed).into(),
+                    None => controller.into()
+                }
+            },
+        }
+    }
+
+    pub fn is_human(&self) -> bool {
+        *self == ControllerKind::Human
+    }
+}
+
+impl FromStr for ControllerKind {
+    type Err = UnknownController;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || UnknownController(s.to_string());
+        let mut parts = s.splitn(2, ':');
+        let name = parts.next().unwrap_or_default().to_lowercase();
+        let parameter = parts.next();
+        let number = || parameter.map(|parameter| parameter.parse::<u32>().map_err(|_| unknown())).transpose();
+
+        match (name.as_str(), parameter) {
+            ("human", None) => Ok(ControllerKind::Human),
+            ("random", seed) => Ok(ControllerKind::Random {
+                seed: seed.map(|seed| seed.parse::<u64>().map_err(|_| unknown())).transpose()?
+            }),
+            ("minimax", _) => Ok(ControllerKind::Minimax { max_depth: number()? }),
+            ("mcts", _) => match number()?.unwrap_or(mcts::MctsController::DEFAULT_ITERATIONS) {
+                0 => Err(unknown()),
+                iterations => Ok(ControllerKind::Mcts { iterations })
+            },
+            (name, _) => match (name.parse(), number()?) {
+                (Ok(difficulty), blunder_percent) if blunder_percent.is_none_or(|percent| percent <= 100) => {
+                    Ok(ControllerKind::Ai { difficulty, blunder_percent })
+                },
+                _ => Err(unknown())
+            }
+        }
+    }
+}
+
+impl Display for ControllerKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ControllerKind::Human => write!(f, "human"),
+            ControllerKind::Random { seed: None } => write!(f, "random"),
+            ControllerKind::Random { seed: Some(seed) } => write!(f, "random:{}", seed),
+            ControllerKind::Minimax { max_depth: None } => write!(f, "minimax"),
+            ControllerKind::Minimax { max_depth: Some(depth) } => write!(f, "minimax:{}", depth),
+            ControllerKind::Mcts { iterations } => write!(f, "mcts:{}", iterations),
+            ControllerKind::Ai { difficulty, blunder_percent: None } => write!(f, "{}", difficulty),
+            ControllerKind::Ai { difficulty, blunder_percent: Some(percent) } => write!(f, "{}:{}", difficulty, percent),
+        }
+    }
+}
+
+impl Display for UnknownController {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Unknown controller \"{}\", expected one of human, random[:seed], minimax[:depth], mcts[:iterations] \
+             or a difficulty (beginner, casual, hard or perfect) with an optional [:blunder percent]",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for UnknownController {}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -235,4 +418,25 @@ mod test {
         let nearest = positions.nearest_position(&start, Direction::Left);
         assert_eq!(nearest, &start);
     }
+
+    #[test]
+    fn parse_controller_kinds() {
+        assert_eq!("human".parse(), Ok(ControllerKind::Human));
+        assert_eq!("Random".parse(), Ok(ControllerKind::Random { seed: None }));
+        assert_eq!("random:12345678901".parse(), Ok(ControllerKind::Random { seed: Some(12345678901) }));
+        assert_eq!("minimax".parse(), Ok(ControllerKind::Minimax { max_depth: None }));
+        assert_eq!("minimax:4".parse(), Ok(ControllerKind::Minimax { max_depth: Some(4) }));
+        assert_eq!("mcts:500".parse(), Ok(ControllerKind::Mcts { iterations: 500 }));
+        assert!("human:3".parse::<ControllerKind>().is_err());
+        assert!("mcts:lots".parse::<ControllerKind>().is_err());
+        assert!("mcts:0".parse::<ControllerKind>().is_err());
+        assert!("alphazero".parse::<ControllerKind>().is_err());
+        assert_eq!("hard".parse(), Ok(ControllerKind::Ai { difficulty: difficulty::Difficulty::Hard, blunder_percent: None }));
+        assert!("casual:101".parse::<ControllerKind>().is_err());
+
+        for kind in &["human", "random", "minimax", "random:7", "minimax:2", "mcts:100", "beginner", "casual:30"] {
+            let parsed: ControllerKind = kind.parse().unwrap();
+            assert_eq!(&parsed.to_string(), kind);
+        }
+    }
 }
@@ -0,0 +1,235 @@
+use crate::game::board::Board;
+use crate::game::board::position::{Position, Seat};
+use crate::game::player::{Controller, Player};
+use crate::game::player::controllers::minimax::MinimaxController;
+use crate::game::Move;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// How well a [`DifficultyController`](struct.DifficultyController.html) plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    Beginner,
+    Casual,
+    Hard,
+    Perfect,
+}
+
+/// Everything a difficulty changes about how moves are chosen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Settings {
+    /// How many moves ahead to search, or `None` to search to the end of the game
+    pub max_depth: Option<u32>,
+    /// The chance of playing a random move instead of the best one found, from 0 to 1
+    pub blunder_rate: f64,
+    /// Whether a move that wins immediately is always played, even instead of a blunder
+    pub takes_wins: bool,
+    /// Whether the opponent's immediate wins are always blocked, even instead of a blunder
+    pub blocks_losses: bool,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Difficulty::Beginner, Difficulty::Casual, Difficulty::Hard, Difficulty::Perfect];
+
+    pub fn settings(self) -> Settings {
+        match self {
+            Difficulty::Beginner => Settings { max_depth: Some(1), blunder_rate: 0.4, takes_wins: false, blocks_losses: false },
+            Difficulty::Casual => Settings { max_depth: Some(2), blunder_rate: 0.2, takes_wins: true, blocks_losses: false },
+            Difficulty::Hard => Settings { max_depth: Some(4), blunder_rate: 0.05, takes_wins: true, blocks_losses: true },
+            Difficulty::Perfect => Settings { max_depth: None, blunder_rate: 0.0, takes_wins: true, blocks_losses: true },
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownDifficulty(String);
+
+impl Display for UnknownDifficulty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown difficulty \"{}\", expected one of beginner, casual, hard or perfect", self.0)
+    }
+}
+
+impl std::error::Error for UnknownDifficulty {}
+
+impl FromStr for Difficulty {
+    type Err = UnknownDifficulty;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL.iter()
+            .copied()
+            .find(|difficulty| difficulty.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| UnknownDifficulty(s.to_string()))
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difficulty::Beginner => write!(f, "beginner"),
+            Difficulty::Casual => write!(f, "casual"),
+            Difficulty::Hard => write!(f, "hard"),
+            Difficulty::Perfect => write!(f, "perfect"),
+        }
+    }
+}
+
+/// Plays with a minimax search that can be made weaker by limiting its depth and by sometimes
+/// playing a random move instead. Moves that score the same are picked between at random, so
+/// games against it vary.
+pub struct DifficultyController {
+    settings: Settings,
+    search: MinimaxController,
+    rng: Mutex<StdRng>,
+}
+
+impl DifficultyController {
+    pub fn new(difficulty: Difficulty) -> Self {
+        Self::with_settings(difficulty.settings())
+    }
+
+    pub fn with_settings(settings: Settings) -> Self {
+        let search = match settings.max_depth {
+            Some(depth) => MinimaxController::with_max_depth(depth),
+            None => MinimaxController::new()
+        };
+
+        Self {
+            settings,
+            search,
+            rng: Mutex::new(StdRng::from_entropy()),
+        }
+    }
+
+    /// Makes the blunders and the choices between equally good moves with a seeded generator
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+            ..self
+        }
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Chooses a move for the seat to move
+    pub fn choose<R: Rng>(&self, position: &mut Position, rng: &mut R) -> Option<(u8, u8)> {
+        let open = position.open_positions();
+        if open.is_empty() {
+            return None;
+        }
+
+        let seat = position.to_move();
+        let opponent = (seat + 1) % Position::SEATS;
+        if self.settings.takes_wins {
+            if let Some(&win) = open.iter().find(|&&(x, y)| Self::completes_line(position, seat, x, y)) {
+                return Some(win);
+            }
+        }
+        if self.settings.blocks_losses {
+            if let Some(&block) = open.iter().find(|&&(x, y)| Self::completes_line(position, opponent, x, y)) {
+                return Some(block);
+            }
+        }
+        if rng.gen_bool(self.settings.blunder_rate.clamp(0.0, 1.0)) {
+            return open.choose(rng).copied();
+        }
+
+        let scores = self.search.evaluate_moves(position);
+        let best = scores.iter().map(|&(_, score)| score).max()?;
+        let best_moves: Vec<(u8, u8)> = scores.into_iter()
+            .filter(|&(_, score)| score == best)
+            .map(|(position, _)| position)
+            .collect();
+        best_moves.choose(rng).copied()
+    }
+
+    /// Whether `seat` would complete a line by placing a mark at (x, y)
+    fn completes_line(position: &Position, seat: Seat, x: u8, y: u8) -> bool {
+        position.config()
+            .line_through(x, y, |line_x, line_y| (line_x, line_y) == (x, y) || position.get(line_x, line_y) == Some(seat))
+            .is_some()
+    }
+}
+
+impl Controller for DifficultyController {
+    fn get_next_move(&self, player: &Arc<Player>, board: &Board) -> Move {
+        let mut position = Position::from_board(board, player);
+        let (x, y) = self.choose(&mut position, &mut *self.rng.lock().unwrap()).expect("There are no open positions to play");
+        Move::new(x, y, player)
+    }
+
+    fn supports_sides(&self, sides: usize) -> bool {
+        sides <= Position::SEATS as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::board::BoardConfig;
+
+    /// x x .
+    /// o o .
+    /// . . .
+    /// with x to move, who can win at (2, 0) or must otherwise block at (2, 1)
+    fn threats() -> Position {
+        let mut position = Position::new(BoardConfig::default());
+        for &(x, y) in &[(0, 0), (0, 1), (1, 0), (1, 1)] {
+            position.play(x, y);
+        }
+        position
+    }
+
+    #[test]
+    fn wins_and_blocks_are_never_blundered() {
+        let always_blunders = |takes_wins, blocks_losses| DifficultyController::with_settings(Settings {
+            max_depth: Some(1),
+            blunder_rate: 1.0,
+            takes_wins,
+            blocks_losses,
+        });
+
+        let mut position = threats();
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            assert_eq!(always_blunders(true, false).choose(&mut position, &mut rng), Some((2, 0)));
+            assert_eq!(always_blunders(false, true).choose(&mut position, &mut rng), Some((2, 1)));
+        }
+
+        // Without either, every open position is eventually played
+        let mut played = std::collections::HashSet::new();
+        for _ in 0..200 {
+            played.insert(always_blunders(false, false).choose(&mut position, &mut rng));
+        }
+        assert_eq!(played.len(), 5);
+    }
+
+    #[test]
+    fn harder_difficulties_search_deeper_and_blunder_less() {
+        for pair in Difficulty::ALL.windows(2) {
+            let (easier, harder) = (pair[0].settings(), pair[1].settings());
+            assert!(harder.blunder_rate < easier.blunder_rate);
+            assert!(harder.max_depth.is_none() || harder.max_depth > easier.max_depth);
+        }
+
+        let mut position = threats();
+        let perfect = DifficultyController::new(Difficulty::Perfect);
+        assert_eq!(perfect.choose(&mut position, &mut StdRng::seed_from_u64(7)), Some((2, 0)));
+    }
+
+    #[test]
+    fn parse_difficulties() {
+        assert_eq!("Casual".parse(), Ok(Difficulty::Casual));
+        assert!("impossible".parse::<Difficulty>().is_err());
+        for difficulty in &Difficulty::ALL {
+            assert_eq!(difficulty.to_string().parse(), Ok(*difficulty));
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use crate::game::board::Board;
+use crate::game::board::position::{Position, Seat};
+use crate::game::player::{Controller, Player};
+use crate::game::Move;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a search is allowed to run. Searches always run at least one iteration, so they can
+/// choose a move whatever the budget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Budget {
+    Iterations(u32),
+    Time(Duration),
+}
+
+/// Plays by running random playouts, guided towards promising moves with the UCT formula
+pub struct MctsController {
+    budget: Budget,
+    exploration: f64,
+    rng: Mutex<StdRng>,
+}
+
+struct Node {
+    position: Option<(u8, u8)>,
+    parent: Option<usize>,
+    /// The seat that played the move leading to this node
+    mover: Seat,
+    children: Vec<usize>,
+    untried: Vec<(u8, u8)>,
+    /// Set when the game is over at this node, holding the winner if there is one
+    result: Option<Option<Seat>>,
+    visits: u32,
+    reward: f64,
+}
+
+impl Node {
+    fn new(position: Option<(u8, u8)>, parent: Option<usize>, mover: Seat, state: &Position, result: Option<Option<Seat>>) -> Self {
+        Self {
+            position,
+            parent,
+            mover,
+            children: vec![],
+            untried: if result.is_some() { vec![] } else { state.open_positions() },
+            result,
+            visits: 0,
+            reward: 0.0,
+        }
+    }
+}
+
+impl MctsController {
+    pub const DEFAULT_ITERATIONS: u32 = 10_000;
+
+    pub fn new() -> Self {
+        Self::with_budget(Budget::Iterations(Self::DEFAULT_ITERATIONS))
+    }
+
+    pub fn with_iterations(iterations: u32) -> Self {
+        Self::with_budget(Budget::Iterations(iterations))
+    }
+
+    pub fn with_time_limit(limit: Duration) -> Self {
+        Self::with_budget(Budget::Time(limit))
+    }
+
+    pub fn with_budget(budget: Budget) -> Self {
+        Self {
+            budget,
+            exploration: std::f64::consts::SQRT_2,
+            rng: Mutex::new(StdRng::from_entropy()),
+        }
+    }
+
+    /// Makes the playouts with a seeded generator. Searches limited by iterations then always
+    /// choose the same moves, while time limited ones still depend on how fast they run.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+            ..self
+        }
+    }
+
+    /// Runs a search from `position`, returning the most visited move for the seat to move
+    pub fn search<R: Rng>(&self, position: &Position, rng: &mut R) -> Option<(u8, u8)> {
+        let root_mover = (position.to_move() + Position::SEATS - 1) % Position::SEATS;
+        let mut tree = vec![Node::new(None, None, root_mover, position, None)];
+        if tree[0].untried.is_empty() {
+            return None;
+        }
+
+        let start = Instant::now();
+        let mut iterations = 0;
+        loop {
+            match self.budget {
+                Budget::Iterations(limit) if iterations > 0 && iterations >= limit => break,
+                Budget::Time(limit) if iterations > 0 && start.elapsed() >= limit => break,
+                _ => {}
+            }
+
+            self.iterate(&mut tree, position.clone(), rng);
+            iterations += 1;
+        }
+
+        tree[0].children.iter()
+            .max_by_key(|&&child| tree[child].visits)
+            .and_then(|&child| tree[child].position)
+    }
+
+    fn iterate<R: Rng>(&self, tree: &mut Vec<Node>, mut state: Position, rng: &mut R) {
+        let mut node = 0;
+
+        // Selection
+        while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+            node = self.select_child(tree, node);
+            let (x, y) = tree[node].position.unwrap();
+            state.play(x, y);
+        }
+
+        // Expansion
+        if !tree[node].untried.is_empty() {
+            let index = rng.gen_range(0, tree[node].untried.len());
+            let (x, y) = tree[node].untried.swap_remove(index);
+            let mover = state.to_move();
+            let result = if state.play(x, y) {
+                Some(Some(mover))
+            } else if state.is_full() {
+                Some(None)
+            } else {
+                None
+            };
+
+            let child = tree.len();
+            tree.push(Node::new(Some((x, y)), Some(node), mover, &state, result));
+            tree[node].children.push(child);
+            node = child;
+        }
+
+        // Simulation
+        let winner = match tree[node].result {
+            Some(result) => result,
+            None => Self::playout(&mut state, rng)
+        };
+
+        // Backpropagation
+        let mut current = Some(node);
+        while let Some(index) = current {
+            let node = &mut tree[index];
+            node.visits += 1;
+            node.reward += match winner {
+                Some(seat) if seat == node.mover => 1.0,
+                Some(_) => 0.0,
+                None => 0.5,
+            };
+            current = node.parent;
+        }
+    }
+
+    fn select_child(&self, tree: &[Node], node: usize) -> usize {
+        let parent_visits = (tree[node].visits as f64).ln();
+        let uct = |child: usize| {
+            let child = &tree[child];
+            let visits = child.visits as f64;
+            child.reward / visits + self.exploration * (parent_visits / visits).sqrt()
+        };
+
+        *tree[node].children.iter()
+            .max_by(|&&a, &&b| uct(a).partial_cmp(&uct(b)).unwrap())
+            .unwrap()
+    }
+
+    /// Plays random moves until the game is over, returning the winner if there is one
+    fn playout<R: Rng>(state: &mut Position, rng: &mut R) -> Option<Seat> {
+        let mut moves = state.open_positions();
+        moves.shuffle(rng);
+
+        for (x, y) in moves {
+            let mover = state.to_move();
+            if state.play(x, y) {
+                return Some(mover);
+            }
+        }
+
+        None
+    }
+}
+
+impl Default for MctsController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Controller for MctsController {
+    fn get_next_move(&self, player: &Arc<Player>, board: &Board) -> Move {
+        let position = Position::from_board(board, player);
+        let (x, y) = self.search(&position, &mut *self.rng.lock().unwrap()).expect("There are no open positions to play");
+        Move::new(x, y, player)
+    }
+
+    fn supports_sides(&self, sides: usize) -> bool {
+        sides <= Position::SEATS as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::board::BoardConfig;
+
+    #[test]
+    fn takes_immediate_win() {
+        // x x .
+        // o o .
+        // . . .
+        let mut position = Position::new(BoardConfig::default());
+        position.play(0, 0);
+        position.play(0, 1);
+        position.play(1, 0);
+        position.play(1, 1);
+
+        let controller = MctsController::with_iterations(2_000);
+        assert_eq!(controller.search(&position, &mut StdRng::seed_from_u64(7)), Some((2, 0)));
+    }
+
+    #[test]
+    fn blocks_immediate_loss() {
+        // x . .
+        // . o .
+        // o . x
+        let mut position = Position::new(BoardConfig::default());
+        position.play(0, 0);
+        position.play(1, 1);
+        position.play(2, 2);
+        position.play(0, 2);
+
+        let controller = MctsController::with_iterations(2_000);
+        assert_eq!(controller.search(&position, &mut StdRng::seed_from_u64(7)), Some((2, 0)));
+    }
+
+    #[test]
+    fn runs_at_least_one_iteration() {
+        let position = Position::new(BoardConfig::default());
+        assert!(MctsController::with_iterations(0).search(&position, &mut StdRng::seed_from_u64(7)).is_some());
+        assert!(MctsController::with_time_limit(Duration::from_secs(0)).search(&position, &mut StdRng::seed_from_u64(7)).is_some());
+    }
+
+    #[test]
+    fn respects_time_limit_on_large_boards() {
+        let position = Position::new(BoardConfig::square(15, 5).unwrap());
+        let controller = MctsController::with_time_limit(Duration::from_millis(50));
+
+        let start = Instant::now();
+        let chosen = controller.search(&position, &mut StdRng::seed_from_u64(7));
+        assert!(chosen.is_some());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
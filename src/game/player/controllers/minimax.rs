@@ -0,0 +1,315 @@
+use crate::game::board::Board;
+use crate::game::board::position::Position;
+use crate::game::player::{Controller, Player};
+use crate::game::player::controllers::transposition::{Bound, SharedTable, TableStats, TranspositionTable};
+use crate::game::Move;
+
+use std::sync::{Arc, MutexGuard};
+
+/// The score of winning immediately. Wins further in the future score one less for every move
+/// it takes to get there, so faster wins and slower losses are preferred.
+pub const WIN_SCORE: i32 = 1_000_000;
+
+/// A move and its score from the point of view of the seat making it
+pub type ScoredMove = ((u8, u8), i32);
+
+/// Plays by searching the game tree with alpha-beta pruning. Positions reached through different
+/// move orders are looked up in a transposition table instead of being searched again.
+pub struct MinimaxController {
+    max_depth: Option<u32>,
+    table: SharedTable,
+}
+
+impl MinimaxController {
+    /// A controller that searches until the end of the game
+    pub fn new() -> Self {
+        Self {
+            max_depth: None,
+            table: TranspositionTable::shared(TranspositionTable::DEFAULT_SLOTS),
+        }
+    }
+
+    /// A controller that stops searching `max_depth` moves ahead, treating unfinished positions
+    /// as draws. Necessary on larger boards, where searching the full tree is infeasible.
+    pub fn with_max_depth(max_depth: u32) -> Self {
+        Self {
+            max_depth: Some(max_depth),
+            ..Self::new()
+        }
+    }
+
+    /// Uses a transposition table that other controllers may also be using, instead of its own
+    pub fn with_table(self, table: SharedTable) -> Self {
+        Self {
+            table,
+            ..self
+        }
+    }
+
+    /// The transposition table, which other controllers may be sharing
+    pub fn table(&self) -> &SharedTable {
+        &self.table
+    }
+
+    /// Finds the best move for the seat to move and its score from that seat's point of view
+    pub fn best_move(&self, position: &mut Position) -> Option<ScoredMove> {
+        self.best_move_with_stats(position).0
+    }
+
+    /// Like [`best_move`](#method.best_move), along with how the search used the transposition table
+    pub fn best_move_with_stats(&self, position: &mut Position) -> (Option<ScoredMove>, TableStats) {
+        let mut search = Search::new(self);
+        let mut best = None;
+        let mut alpha = -WIN_SCORE - 1;
+        let beta = WIN_SCORE + 1;
+
+        for (x, y) in Self::ordered_moves(position, None) {
+            let score = search.score_move(position, x, y, 0, alpha, beta);
+            if score > alpha {
+                alpha = score;
+                best = Some(((x, y), score));
+            }
+        }
+
+        (best, search.stats)
+    }
+
+    /// Scores every open position for the seat to move
+    pub fn evaluate_moves(&self, position: &mut Position) -> Vec<ScoredMove> {
+        self.evaluate_moves_with_stats(position).0
+    }
+
+    /// Like [`evaluate_moves`](#method.evaluate_moves), along with how the search used the
+    /// transposition table
+    pub fn evaluate_moves_with_stats(&self, position: &mut Position) -> (Vec<ScoredMove>, TableStats) {
+        let mut search = Search::new(self);
+        let scores = Self::ordered_moves(position, None)
+            .into_iter()
+            .map(|(x, y)| ((x, y), search.score_move(position, x, y, 0, -WIN_SCORE - 1, WIN_SCORE + 1)))
+            .collect();
+        (scores, search.stats)
+    }
+
+    /// Locks the table for a single probe or store, so searches sharing it can run at the same time
+    fn lock_table(&self) -> MutexGuard<'_, TranspositionTable> {
+        self.table.lock().expect("A search panicked while holding the table")
+    }
+
+    /// Open positions ordered from the center outwards, which tends to find good moves first.
+    /// The best move found by an earlier search of the position goes before all of them.
+    fn ordered_moves(position: &Position, first: Option<usize>) -> Vec<(u8, u8)> {
+        let config = position.config();
+        let center_x = config.width() as i32 - 1;
+        let center_y = config.height() as i32 - 1;
+
+        let mut moves = position.open_positions();
+        moves.sort_by_key(|&(x, y)| {
+            let distance = (2 * x as i32 - center_x).abs() + (2 * y as i32 - center_y).abs();
+            (Some(config.index_of(x, y)) != first, distance)
+        });
+        moves
+    }
+}
+
+/// The state of a single search, which counts its own use of the table so searches sharing one
+/// don't mix up their statistics
+struct Search<'a> {
+    controller: &'a MinimaxController,
+    stats: TableStats,
+}
+
+impl<'a> Search<'a> {
+    /// Starts a search, marking the entries of earlier searches as replaceable
+    fn new(controller: &'a MinimaxController) -> Self {
+        controller.lock_table().new_search();
+        Self {
+            controller,
+            stats: TableStats::default(),
+        }
+    }
+
+    fn score_move(&mut self, position: &mut Position, x: u8, y: u8, depth: u32, alpha: i32, beta: i32) -> i32 {
+        let score = if position.play(x, y) {
+            WIN_SCORE - depth as i32
+        } else if position.is_full() {
+            0
+        } else {
+            -self.negamax(position, depth + 1, -beta, -alpha)
+        };
+        position.undo(x, y);
+        score
+    }
+
+    fn negamax(&mut self, position: &mut Position, depth: u32, mut alpha: i32, mut beta: i32) -> i32 {
+        let open = (position.config().size() - position.filled_positions()) as u32;
+        let remaining = match self.controller.max_depth {
+            Some(max_depth) if depth >= max_depth => return 0,
+            Some(max_depth) => (max_depth - depth).min(open),
+            None => open
+        };
+
+        let original_alpha = alpha;
+        let entry = self.controller.lock_table().probe(position.hash(), &mut self.stats);
+        if let Some(entry) = entry.filter(|entry| entry.depth >= remaining) {
+            let score = from_table(entry.score, depth);
+            match entry.bound {
+                Bound::Exact => return score,
+                Bound::Lower => alpha = alpha.max(score),
+                Bound::Upper => beta = beta.min(score),
+            }
+            if alpha >= beta {
+                return score;
+            }
+        }
+
+        let mut best = -WIN_SCORE - 1;
+        let mut best_move = None;
+        for (x, y) in MinimaxController::ordered_moves(position, entry.and_then(|entry| entry.best_move)) {
+            let score = self.score_move(position, x, y, depth, alpha, beta);
+            if score > best {
+                best = score;
+                best_move = Some(position.config().index_of(x, y));
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.controller.lock_table().store(position.hash(), to_table(best, depth), bound, remaining, best_move, &mut self.stats);
+
+        best
+    }
+}
+
+/// Wins and losses score by how far from the root they are, so they are stored by how far from
+/// the stored position they are instead, which holds wherever the position is found again
+fn to_table(score: i32, depth: u32) -> i32 {
+    if score > WIN_SCORE / 2 {
+        score + depth as i32
+    } else if score < -WIN_SCORE / 2 {
+        score - depth as i32
+    } else {
+        score
+    }
+}
+
+fn from_table(score: i32, depth: u32) -> i32 {
+    if score > WIN_SCORE / 2 {
+        score - depth as i32
+    } else if score < -WIN_SCORE / 2 {
+        score + depth as i32
+    } else {
+        score
+    }
+}
+
+impl Default for MinimaxController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Controller for MinimaxController {
+    fn get_next_move(&self, player: &Arc<Player>, board: &Board) -> Move {
+        let mut position = Position::from_board(board, player);
+        let ((x, y), _) = self.best_move(&mut position).expect("There are no open positions to play");
+        Move::new(x, y, player)
+    }
+
+    /// Searches see the game as two seats taking turns, so they can't play against more than
+    /// one other side
+    fn supports_sides(&self, sides: usize) -> bool {
+        sides <= Position::SEATS as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::player::{PlayerBuilder, SelfController};
+    use crate::game::player::controllers::HumanController;
+
+    /// Plays every possible sequence of opponent moves against the AI, checking the AI never loses
+    fn never_loses(board: &Board, ai: &Arc<Player>, opponent: &Arc<Player>, ai_to_move: bool) {
+        if ai_to_move {
+            let mut board = board.clone();
+            let next_move = ai.next_move(&board);
+            if board.make_move(next_move).expect("AI should make legal moves").is_some() || board.is_full() {
+                return;
+            }
+            never_loses(&board, ai, opponent, false);
+        } else {
+            for (x, y) in board.get_open_positions() {
+                let mut board = board.clone();
+                let result = board.make_move(Move::new(x, y, opponent)).unwrap();
+                assert!(result.is_none(), "AI lost:\n{}", board);
+                if !board.is_full() {
+                    never_loses(&board, ai, opponent, true);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn never_loses_on_standard_board() {
+        let builder = PlayerBuilder::new();
+        let ai = Arc::new(builder.new_player('x', MinimaxController::new().into()).unwrap());
+        let opponent = Arc::new(builder.new_player('o', HumanController.into()).unwrap());
+
+        never_loses(&Board::new(), &ai, &opponent, true);
+        never_loses(&Board::new(), &ai, &opponent, false);
+    }
+
+    #[test]
+    fn prefers_faster_wins() {
+        // x x .
+        // o o .
+        // . . .
+        // x can win now at (2, 0), or later, but should not block instead
+        let mut position = Position::new(Default::default());
+        position.play(0, 0);
+        position.play(0, 1);
+        position.play(1, 0);
+        position.play(1, 1);
+
+        let controller = MinimaxController::new();
+        assert_eq!(controller.best_move(&mut position), Some(((2, 0), WIN_SCORE)));
+    }
+
+    #[test]
+    fn shared_tables_are_reused_between_searches() {
+        let table = TranspositionTable::shared(1 << 12);
+        let first = MinimaxController::new().with_table(table.clone());
+        let second = MinimaxController::new().with_table(table.clone());
+
+        let mut position = Position::new(Default::default());
+        position.play(1, 1);
+        let (cold_best, cold) = first.best_move_with_stats(&mut position);
+        let (best, score) = cold_best.unwrap();
+        assert_eq!(score, 0);
+        assert!(cold.hits > 0, "Transpositions are found within a single search");
+
+        // The second search finds the whole tree already scored
+        let (warm_best, warm) = second.best_move_with_stats(&mut position);
+        assert_eq!(warm_best, Some((best, score)));
+        assert!(warm.probes < cold.probes);
+        assert!(warm.hit_rate() > cold.hit_rate());
+    }
+
+    #[test]
+    fn empty_board_is_a_draw() {
+        let mut position = Position::new(Default::default());
+        let controller = MinimaxController::new();
+        for (_, score) in controller.evaluate_moves(&mut position) {
+            assert_eq!(score, 0);
+        }
+    }
+}
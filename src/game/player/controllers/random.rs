@@ -0,0 +1,71 @@
+use crate::game::board::Board;
+use crate::game::player::{Controller, Player};
+use crate::game::Move;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::sync::{Arc, Mutex};
+
+/// Plays uniformly at random among the open positions. A controller created with a seed plays
+/// the same moves every time it is given the same boards.
+pub struct RandomController {
+    rng: Mutex<StdRng>,
+}
+
+impl RandomController {
+    pub fn new() -> Self {
+        Self {
+            rng: Mutex::new(StdRng::from_entropy()),
+        }
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+}
+
+impl Default for RandomController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Controller for RandomController {
+    fn get_next_move(&self, player: &Arc<Player>, board: &Board) -> Move {
+        let (x, y) = *board.get_open_positions()
+            .choose(&mut *self.rng.lock().unwrap())
+            .expect("There are no open positions to play");
+        Move::new(x, y, player)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::player::PlayerBuilder;
+
+    /// The positions `controller` picks when asked for 20 moves on an empty board
+    fn choices(controller: &RandomController) -> Vec<(u8, u8)> {
+        let board = Board::new();
+        let player = Arc::new(PlayerBuilder::new().new_player('x', RandomController::new().into()).unwrap());
+        (0..20)
+            .map(|_| {
+                let chosen = controller.get_next_move(&player, &board);
+                (chosen.x_pos, chosen.y_pos)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn seeded_controllers_repeat_their_moves() {
+        let seeded = choices(&RandomController::with_seed(7));
+        assert_eq!(seeded, choices(&RandomController::with_seed(7)));
+        assert_ne!(seeded, choices(&RandomController::with_seed(8)));
+
+        let open = Board::new().get_open_positions();
+        assert!(choices(&RandomController::new()).iter().all(|chosen| open.contains(chosen)));
+    }
+}
@@ -0,0 +1,154 @@
+use std::sync::{Arc, Mutex};
+
+/// How a stored score relates to the true score of a position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    /// The search was cut off, so the true score is at least the stored one
+    Lower,
+    /// No move reached the lower end of the search window, so the true score is at most the stored one
+    Upper,
+}
+
+/// A position scored by a search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    /// The Zobrist hash of the position
+    pub key: u64,
+    pub score: i32,
+    pub bound: Bound,
+    /// How many moves deep the position was searched
+    pub depth: u32,
+    /// The index of the best move found, if there was one
+    pub best_move: Option<usize>,
+    generation: u32,
+}
+
+/// How a search used the table. Every search counts its own probes and stores, so searches
+/// sharing a table don't mix up their statistics.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TableStats {
+    pub probes: u64,
+    pub hits: u64,
+    pub stores: u64,
+    /// Stores that overwrote an entry for a different position
+    pub replacements: u64,
+}
+
+impl TableStats {
+    /// The fraction of probes that found their position, or 0 if there were none
+    pub fn hit_rate(&self) -> f64 {
+        if self.probes == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.probes as f64
+        }
+    }
+}
+
+/// A fixed size cache of scored positions, indexed by their Zobrist hash.
+///
+/// When two positions map to the same slot, entries from an earlier search are always replaced,
+/// and entries from the current search are only replaced by ones searched at least as deeply,
+/// as those save the most work.
+pub struct TranspositionTable {
+    slots: Vec<Option<Entry>>,
+    generation: u32,
+}
+
+/// A table shared by several controllers. Searches only lock it to probe or store an entry, so
+/// several can run at the same time.
+pub type SharedTable = Arc<Mutex<TranspositionTable>>;
+
+impl TranspositionTable {
+    pub const DEFAULT_SLOTS: usize = 1 << 16;
+
+    /// Creates a table with room for `slots` entries, rounded up to a power of two
+    pub fn new(slots: usize) -> Self {
+        Self {
+            slots: vec![None; slots.max(1).next_power_of_two()],
+            generation: 0,
+        }
+    }
+
+    pub fn shared(slots: usize) -> SharedTable {
+        Arc::new(Mutex::new(Self::new(slots)))
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Marks every entry as coming from an earlier search
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        key as usize & (self.slots.len() - 1)
+    }
+
+    /// Looks up the entry for `key`, counting the probe in the statistics of the search doing it
+    pub fn probe(&self, key: u64, stats: &mut TableStats) -> Option<Entry> {
+        stats.probes += 1;
+        let entry = self.slots[self.slot(key)].filter(|entry| entry.key == key)?;
+        stats.hits += 1;
+        Some(entry)
+    }
+
+    /// Stores the entry for `key` if it is worth keeping, counting the store in the statistics of
+    /// the search doing it
+    pub fn store(&mut self, key: u64, score: i32, bound: Bound, depth: u32, best_move: Option<usize>, stats: &mut TableStats) {
+        let generation = self.generation;
+        let slot = self.slot(key);
+        let replace = match &self.slots[slot] {
+            None => true,
+            Some(existing) => existing.key == key || existing.generation != generation || depth >= existing.depth
+        };
+        if !replace {
+            return;
+        }
+
+        if self.slots[slot].is_some_and(|existing| existing.key != key) {
+            stats.replacements += 1;
+        }
+        stats.stores += 1;
+        self.slots[slot] = Some(Entry { key, score, bound, depth, best_move, generation });
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SLOTS)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn deeper_entries_are_kept_within_a_search() {
+        let mut table = TranspositionTable::new(3);
+        let mut stats = TableStats::default();
+        assert_eq!(table.capacity(), 4);
+
+        // Keys 1 and 5 share a slot
+        table.store(1, 10, Bound::Exact, 4, Some(2), &mut stats);
+        table.store(5, 20, Bound::Lower, 2, None, &mut stats);
+        assert_eq!(table.probe(1, &mut stats).map(|entry| entry.score), Some(10));
+        assert_eq!(table.probe(5, &mut stats), None);
+
+        table.store(5, 20, Bound::Lower, 6, None, &mut stats);
+        assert_eq!(table.probe(5, &mut stats).map(|entry| entry.bound), Some(Bound::Lower));
+        assert_eq!(stats.replacements, 1);
+        assert_eq!(stats.hit_rate(), 2.0 / 3.0);
+
+        // Anything from an earlier search can be replaced
+        table.new_search();
+        let mut stats = TableStats::default();
+        table.store(1, 30, Bound::Upper, 0, None, &mut stats);
+        assert_eq!(table.probe(1, &mut stats).map(|entry| entry.score), Some(30));
+        assert_eq!(stats, TableStats { probes: 1, hits: 1, stores: 1, replacements: 1 });
+    }
+}
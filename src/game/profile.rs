@@ -0,0 +1,292 @@
+//! Player profiles kept in a local file, so people can come back as the same player.
+//!
+//! A profile holds the name a player is known and rated under, the name shown during games,
+//! the symbol they like to play with and the color their marks are drawn in. Their lifetime
+//! results and rating are kept with everyone else's [`Ratings`](../rating/struct.Ratings.html)
+//! under the profile's name, and are updated after every rated game they play. The file has a
+//! line for every profile with its name, display name, symbol and color separated by tabs,
+//! using `-` for profiles without a color. Lines starting with `#` are comments.
+
+use crate::game::rating::{Rating, Ratings};
+use crate::game::tab_separated;
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fmt::Result as FmtResult;
+use std::path::Path;
+use std::str::FromStr;
+use std::{fs, io};
+
+const FIELDS: [&str; 4] = ["name", "display name", "symbol", "color"];
+
+/// The colors a player's marks can be drawn in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    pub const ALL: [Color; 6] = [Color::Red, Color::Green, Color::Yellow, Color::Blue, Color::Magenta, Color::Cyan];
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownColor(String);
+
+impl Display for UnknownColor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "Unknown color \"{}\", expected one of red, green, yellow, blue, magenta or cyan", self.0)
+    }
+}
+
+impl std::error::Error for UnknownColor {}
+
+impl FromStr for Color {
+    type Err = UnknownColor;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Color::ALL.iter()
+            .copied()
+            .find(|color| color.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| UnknownColor(s.to_string()))
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Color::Red => write!(f, "red"),
+            Color::Green => write!(f, "green"),
+            Color::Yellow => write!(f, "yellow"),
+            Color::Blue => write!(f, "blue"),
+            Color::Magenta => write!(f, "magenta"),
+            Color::Cyan => write!(f, "cyan"),
+        }
+    }
+}
+
+/// Everything about a player that carries over between games, apart from their results
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// The name the player is rated under, which stays the same
+    pub name: String,
+    /// The name shown during games
+    pub display_name: String,
+    /// The symbol the player plays with unless told otherwise
+    pub symbol: char,
+    pub color: Option<Color>,
+}
+
+impl Profile {
+    /// A profile shown under its own name, without a color
+    pub fn new(name: &str, symbol: char) -> Self {
+        Self {
+            name: name.to_string(),
+            display_name: name.to_string(),
+            symbol,
+            color: None,
+        }
+    }
+
+    pub fn with_display_name(self, display_name: &str) -> Self {
+        Self {
+            display_name: display_name.to_string(),
+            ..self
+        }
+    }
+
+    pub fn with_color(self, color: Color) -> Self {
+        Self {
+            color: Some(color),
+            ..self
+        }
+    }
+
+    /// The player's lifetime results and rating, which are the initial ones until they finish a
+    /// rated game
+    pub fn rating(&self, ratings: &Ratings) -> Rating {
+        ratings.get(&self.name).copied().unwrap_or_default()
+    }
+}
+
+#[derive(Debug)]
+pub enum ProfileError {
+    Io(io::Error),
+    InvalidLine { line: usize, reason: String },
+    /// Names can't be empty, start with `#`, or contain tabs or line breaks
+    InvalidName(String),
+    /// Symbols can't be whitespace, or the `-` used for a missing color
+    InvalidSymbol(char),
+    Exists(String),
+    NotFound(String),
+}
+
+impl Display for ProfileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ProfileError::Io(error) => write!(f, "Could not access the profiles: {}", error),
+            ProfileError::InvalidLine { line, reason } => write!(f, "Invalid profile on line {}: {}", line, reason),
+            ProfileError::InvalidName(name) => {
+                write!(f, "\"{}\" can't be used as a name, names can't be empty, start with # or contain tabs or line breaks", name)
+            },
+            ProfileError::InvalidSymbol(symbol) => write!(f, "'{}' can't be used as a symbol", symbol),
+            ProfileError::Exists(name) => write!(f, "There is already a profile named \"{}\"", name),
+            ProfileError::NotFound(name) => write!(f, "There is no profile named \"{}\"", name),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ProfileError::Io(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for ProfileError {
+    fn from(error: io::Error) -> Self {
+        ProfileError::Io(error)
+    }
+}
+
+/// Every profile, by name
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Profiles {
+    profiles: BTreeMap<String, Profile>,
+}
+
+impl Profiles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the profiles in `path`, or no profiles if the file doesn't exist yet
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ProfileError> {
+        tab_separated::read(path)?.parse()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ProfileError> {
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Profile, ProfileError> {
+        self.profiles.get(name).ok_or_else(|| ProfileError::NotFound(name.to_string()))
+    }
+
+    /// Every profile, ordered by name
+    pub fn iter(&self) -> impl Iterator<Item = &Profile> {
+        self.profiles.values()
+    }
+
+    /// Adds a new profile, unless one with the same name exists
+    pub fn create(&mut self, profile: Profile) -> Result<(), ProfileError> {
+        for name in &[&profile.name, &profile.display_name] {
+            if !tab_separated::is_valid_name(name) {
+                return Err(ProfileError::InvalidName(name.to_string()));
+            }
+        }
+        if profile.symbol.is_whitespace() || profile.symbol == '-' {
+            return Err(ProfileError::InvalidSymbol(profile.symbol));
+        }
+        if self.profiles.contains_key(&profile.name) {
+            return Err(ProfileError::Exists(profile.name));
+        }
+
+        self.profiles.insert(profile.name.clone(), profile);
+        Ok(())
+    }
+
+    pub fn delete(&mut self, name: &str) -> Result<Profile, ProfileError> {
+        self.profiles.remove(name).ok_or_else(|| ProfileError::NotFound(name.to_string()))
+    }
+}
+
+impl Display for Profiles {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        tab_separated::write_header(f, &FIELDS)?;
+        for profile in self.iter() {
+            let color = profile.color.map_or_else(|| "-".to_string(), |color| color.to_string());
+            writeln!(f, "{}\t{}\t{}\t{}", profile.name, profile.display_name, profile.symbol, color)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Profiles {
+    type Err = ProfileError;
+
+    fn from_str(text: &str) -> Result<Self, ProfileError> {
+        let mut profiles = Profiles::new();
+        tab_separated::parse(text, &FIELDS, |fields| {
+            let mut symbol = fields[2].chars();
+            let symbol = match (symbol.next(), symbol.next()) {
+                (Some(symbol), None) => symbol,
+                _ => return Err(format!("Symbols must be a single character, got \"{}\"", fields[2]))
+            };
+            let color = match fields[3] {
+                "-" => None,
+                color => Some(color.parse().map_err(|error: UnknownColor| error.to_string())?)
+            };
+
+            let profile = Profile {
+                name: fields[0].to_string(),
+                display_name: fields[1].to_string(),
+                symbol,
+                color,
+            };
+            profiles.create(profile).map_err(|error| error.to_string())
+        }).map_err(|(line, reason)| ProfileError::InvalidLine { line, reason })?;
+        Ok(profiles)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn profiles_round_trip() {
+        let mut profiles = Profiles::new();
+        profiles.create(Profile::new("alice", 'a').with_color(Color::Cyan)).unwrap();
+        profiles.create(Profile::new("bob", '#').with_display_name("Bob the Builder #1")).unwrap();
+
+        let written = profiles.to_string();
+        assert_eq!(written.parse::<Profiles>().unwrap(), profiles);
+        assert!("alice\talice\tab\t-".parse::<Profiles>().is_err());
+        assert!("alice\talice\ta\tpurple".parse::<Profiles>().is_err());
+        assert!("alice\talice\ta\t-\nalice\tAlice\tb\t-".parse::<Profiles>().is_err());
+    }
+
+    #[test]
+    fn profiles_are_created_and_deleted_by_name() {
+        let mut profiles = Profiles::new();
+        profiles.create(Profile::new("alice", 'a')).unwrap();
+        assert!(matches!(profiles.create(Profile::new("alice", 'b')), Err(ProfileError::Exists(_))));
+        assert!(matches!(profiles.create(Profile::new("bob\tsmith", 'b')), Err(ProfileError::InvalidName(_))));
+        assert!(matches!(profiles.create(Profile::new("#bob", 'b')), Err(ProfileError::InvalidName(_))));
+        assert!(matches!(profiles.create(Profile::new("bob", ' ')), Err(ProfileError::InvalidSymbol(' '))));
+
+        assert_eq!(profiles.delete("alice").unwrap().symbol, 'a');
+        assert!(matches!(profiles.get("alice"), Err(ProfileError::NotFound(_))));
+        assert!(matches!(profiles.delete("alice"), Err(ProfileError::NotFound(_))));
+    }
+
+    #[test]
+    fn results_come_from_the_ratings() {
+        let profile = Profile::new("alice", 'a');
+        let mut ratings = Ratings::new();
+        assert_eq!(profile.rating(&ratings), Rating::default());
+
+        ratings.record_game("alice", "bob", 1.0).unwrap();
+        let rating = profile.rating(&ratings);
+        assert_eq!((rating.wins, rating.games()), (1, 1));
+        assert!(rating.elo > Rating::INITIAL_ELO);
+    }
+}
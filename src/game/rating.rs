@@ -0,0 +1,429 @@
+//! Ratings of named players, updated after every finished game and kept in a local file.
+//!
+//! Every player has an Elo rating, and a Glicko-2 rating that also tracks how certain it is.
+//! Each game is rated on its own, as a Glicko-2 rating period with a single game. The file has
+//! a line for every player, starting with their name and followed by their Elo rating, wins,
+//! draws, losses, and Glicko-2 rating, deviation and volatility, all separated by tabs. Lines
+//! starting with `#` are comments.
+
+use crate::game::cycle::GameOutcome;
+use crate::game::player::Player;
+use crate::game::tab_separated::{self, is_valid_name};
+
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::fmt::{Display, Formatter};
+use std::fmt::Result as FmtResult;
+use std::path::Path;
+use std::{fs, io};
+
+const FIELDS: [&str; 8] = ["name", "elo", "wins", "draws", "losses", "glicko", "deviation", "volatility"];
+
+/// A Glicko-2 rating, on the familiar Glicko scale where new players start at 1500
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glicko {
+    pub rating: f64,
+    /// How uncertain the rating is. The true strength is within twice the deviation of the
+    /// rating with 95% confidence.
+    pub deviation: f64,
+    /// How erratic the player's results are
+    pub volatility: f64,
+}
+
+/// Converts between the Glicko scale and the internal Glicko-2 scale
+const GLICKO_SCALE: f64 = 173.7178;
+
+impl Glicko {
+    /// How much the volatility can change over time
+    pub const TAU: f64 = 0.5;
+
+    /// Rates a game against `opponent`, where `score` is 1 for a win, 0.5 for a draw and 0 for a loss
+    pub fn updated(&self, opponent: &Glicko, score: f64) -> Glicko {
+        let mu = (self.rating - 1500.0) / GLICKO_SCALE;
+        let phi = self.deviation / GLICKO_SCALE;
+        let opponent_mu = (opponent.rating - 1500.0) / GLICKO_SCALE;
+        let opponent_phi = opponent.deviation / GLICKO_SCALE;
+
+        let g = 1.0 / (1.0 + 3.0 * opponent_phi.powi(2) / PI.powi(2)).sqrt();
+        let expected = 1.0 / (1.0 + (-g * (mu - opponent_mu)).exp());
+        let variance = 1.0 / (g.powi(2) * expected * (1.0 - expected));
+        let delta = variance * g * (score - expected);
+
+        let volatility = self.new_volatility(phi, variance, delta);
+        let phi_star = (phi.powi(2) + volatility.powi(2)).sqrt();
+        let new_phi = 1.0 / (1.0 / phi_star.powi(2) + 1.0 / variance).sqrt();
+        let new_mu = mu + new_phi.powi(2) * g * (score - expected);
+
+        Glicko {
+            rating: new_mu * GLICKO_SCALE + 1500.0,
+            deviation: new_phi * GLICKO_SCALE,
+            volatility,
+        }
+    }
+
+    /// Solves for the new volatility with the Illinois algorithm, as in step 5 of Glickman's
+    /// description of Glicko-2
+    fn new_volatility(&self, phi: f64, variance: f64, delta: f64) -> f64 {
+        const EPSILON: f64 = 0.000_001;
+        let a = (self.volatility.powi(2)).ln();
+        let f = |x: f64| {
+            let exp = x.exp();
+            let denominator = phi.powi(2) + variance + exp;
+            exp * (delta.powi(2) - phi.powi(2) - variance - exp) / (2.0 * denominator.powi(2))
+                - (x - a) / Self::TAU.powi(2)
+        };
+
+        // The bounds are named A and B in the description
+        let mut x_a = a;
+        let mut x_b = if delta.powi(2) > phi.powi(2) + variance {
+            (delta.powi(2) - phi.powi(2) - variance).ln()
+        } else {
+            let mut k = 1.0;
+            while f(a - k * Self::TAU) < 0.0 {
+                k += 1.0;
+            }
+            a - k * Self::TAU
+        };
+
+        let (mut f_a, mut f_b) = (f(x_a), f(x_b));
+        while (x_b - x_a).abs() > EPSILON {
+            let x_c = x_a + (x_a - x_b) * f_a / (f_b - f_a);
+            let f_c = f(x_c);
+            if f_c * f_b <= 0.0 {
+                x_a = x_b;
+                f_a = f_b;
+            } else {
+                f_a /= 2.0;
+            }
+            x_b = x_c;
+            f_b = f_c;
+        }
+
+        (x_a / 2.0).exp()
+    }
+}
+
+impl Default for Glicko {
+    fn default() -> Self {
+        Self {
+            rating: 1500.0,
+            deviation: 350.0,
+            volatility: 0.06,
+        }
+    }
+}
+
+/// A player's ratings and record
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rating {
+    pub elo: f64,
+    pub glicko: Glicko,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Rating {
+    pub const INITIAL_ELO: f64 = 1500.0;
+
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Counts a game the player scored `score` in towards their wins, draws and losses
+    fn count(&mut self, score: f64) {
+        if score == 1.0 {
+            self.wins += 1;
+        } else if score == 0.0 {
+            self.losses += 1;
+        } else {
+            self.draws += 1;
+        }
+    }
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            elo: Self::INITIAL_ELO,
+            glicko: Glicko::default(),
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RatingsError {
+    Io(io::Error),
+    InvalidLine { line: usize, reason: String },
+    /// Names can't be empty, start with `#`, or contain tabs or line breaks
+    InvalidName(String),
+}
+
+impl Display for RatingsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            RatingsError::Io(error) => write!(f, "Could not access the ratings: {}", error),
+            RatingsError::InvalidLine { line, reason } => write!(f, "Invalid rating on line {}: {}", line, reason),
+            RatingsError::InvalidName(name) => {
+                write!(f, "\"{}\" can't be rated, names can't be empty, start with # or contain tabs or line breaks", name)
+            },
+        }
+    }
+}
+
+impl std::error::Error for RatingsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RatingsError::Io(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for RatingsError {
+    fn from(error: io::Error) -> Self {
+        RatingsError::Io(error)
+    }
+}
+
+/// The ratings of every player that has finished a rated game
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ratings {
+    players: BTreeMap<String, Rating>,
+    k_factor: f64,
+}
+
+impl Ratings {
+    /// How far a single game can move an Elo rating
+    pub const DEFAULT_K_FACTOR: f64 = 32.0;
+
+    pub fn new() -> Self {
+        Self {
+            players: BTreeMap::new(),
+            k_factor: Self::DEFAULT_K_FACTOR,
+        }
+    }
+
+    pub fn set_k_factor(&mut self, k_factor: f64) {
+        self.k_factor = k_factor;
+    }
+
+    /// Loads the ratings in `path`, or no ratings if the file doesn't exist yet
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, RatingsError> {
+        tab_separated::read(path)?.parse()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), RatingsError> {
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Rating> {
+        self.players.get(name)
+    }
+
+    /// Checks that games played under `name` can be rated, before they are played
+    pub fn check_name(name: &str) -> Result<(), RatingsError> {
+        if is_valid_name(name) {
+            Ok(())
+        } else {
+            Err(RatingsError::InvalidName(name.to_string()))
+        }
+    }
+
+    /// Forgets a player's rating and results
+    pub fn remove(&mut self, name: &str) -> Option<Rating> {
+        self.players.remove(name)
+    }
+
+    /// Rates a game between two named players, where `score` is the first player's: 1 for a
+    /// win, 0.5 for a draw and 0 for a loss
+    pub fn record_game(&mut self, first: &str, second: &str, score: f64) -> Result<(), RatingsError> {
+        Self::check_name(first)?;
+        Self::check_name(second)?;
+
+        let first_rating = self.get(first).copied().unwrap_or_default();
+        let second_rating = self.get(second).copied().unwrap_or_default();
+
+        let expected = 1.0 / (1.0 + 10f64.powf((second_rating.elo - first_rating.elo) / 400.0));
+        let change = self.k_factor * (score - expected);
+
+        let updated = |rating: Rating, opponent: &Rating, score: f64, change: f64| {
+            let mut rating = rating;
+            rating.elo += change;
+            rating.glicko = rating.glicko.updated(&opponent.glicko, score);
+            rating.count(score);
+            rating
+        };
+        let new_first = updated(first_rating, &second_rating, score, change);
+        let new_second = updated(second_rating, &first_rating, 1.0 - score, -change);
+
+        self.players.insert(first.to_string(), new_first);
+        self.players.insert(second.to_string(), new_second);
+        Ok(())
+    }
+
+    /// Counts a game towards a named player's wins, draws and losses without rating it, for
+    /// games against an opponent who has no rating to compare with
+    pub fn record_result(&mut self, name: &str, score: f64) -> Result<(), RatingsError> {
+        Self::check_name(name)?;
+        self.players.entry(name.to_string()).or_default().count(score);
+        Ok(())
+    }
+
+    /// Records a finished game for its named players. Games between two named players are
+    /// rated, while a named player against an unnamed one only has the result counted. Returns
+    /// whether anything was recorded, which it never is for abandoned games.
+    pub fn record_outcome(&mut self, player1: &Player, player2: &Player, outcome: &GameOutcome) -> Result<bool, RatingsError> {
+        let score = match outcome {
+            GameOutcome::Win { player, .. } if **player == *player1 => 1.0,
+            GameOutcome::Win { .. } => 0.0,
+            GameOutcome::Draw => 0.5,
+            GameOutcome::Aborted { .. } => return Ok(false),
+        };
+
+        match (player1.name(), player2.name()) {
+            (Some(first), Some(second)) => self.record_game(first, second, score)?,
+            (Some(first), None) => self.record_result(first, score)?,
+            (None, Some(second)) => self.record_result(second, 1.0 - score)?,
+            (None, None) => return Ok(false)
+        }
+        Ok(true)
+    }
+
+    /// Every rated player, from the highest Elo rating to the lowest
+    pub fn leaderboard(&self) -> Vec<(&str, &Rating)> {
+        let mut players: Vec<(&str, &Rating)> = self.players.iter()
+            .map(|(name, rating)| (name.as_str(), rating))
+            .collect();
+        players.sort_by(|(_, first), (_, second)| second.elo.total_cmp(&first.elo));
+        players
+    }
+}
+
+impl Default for Ratings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for Ratings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        tab_separated::write_header(f, &FIELDS)?;
+        for (name, rating) in &self.players {
+            writeln!(
+                f, "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                name, rating.elo, rating.wins, rating.draws, rating.losses,
+                rating.glicko.rating, rating.glicko.deviation, rating.glicko.volatility
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Ratings {
+    type Err = RatingsError;
+
+    fn from_str(text: &str) -> Result<Self, RatingsError> {
+        let mut ratings = Ratings::new();
+        tab_separated::parse(text, &FIELDS, |fields| {
+            let number = |field: &str| field.parse::<f64>().ok().filter(|value| value.is_finite());
+            let count = |field: &str| field.parse::<u32>().ok();
+
+            let rating = (|| Some(Rating {
+                elo: number(fields[1])?,
+                wins: count(fields[2])?,
+                draws: count(fields[3])?,
+                losses: count(fields[4])?,
+                glicko: Glicko {
+                    rating: number(fields[5])?,
+                    deviation: number(fields[6])?,
+                    volatility: number(fields[7])?,
+                },
+            }))().ok_or("Ratings are numbers and results are whole numbers")?;
+
+            if !is_valid_name(fields[0]) || ratings.players.insert(fields[0].to_string(), rating).is_some() {
+                return Err("Every player needs a distinct name".to_string());
+            }
+            Ok(())
+        }).map_err(|(line, reason)| RatingsError::InvalidLine { line, reason })?;
+        Ok(ratings)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::player::PlayerBuilder;
+    use crate::game::player::controllers::HumanController;
+    use std::sync::Arc;
+
+    #[test]
+    fn glicko_matches_the_published_example() {
+        // The example from Glickman's description of Glicko-2, rated one game at a time instead
+        // of as one period, so only the first game matches exactly
+        let player = Glicko { rating: 1500.0, deviation: 200.0, volatility: 0.06 };
+        let opponent = Glicko { rating: 1400.0, deviation: 30.0, volatility: 0.06 };
+        let updated = player.updated(&opponent, 1.0);
+        assert!((updated.rating - 1563.6).abs() < 0.1, "{:?}", updated);
+        assert!((updated.deviation - 175.4).abs() < 0.1, "{:?}", updated);
+        assert!((updated.volatility - 0.06).abs() < 0.0001, "{:?}", updated);
+    }
+
+    #[test]
+    fn wins_move_ratings_apart() {
+        let mut ratings = Ratings::new();
+        ratings.record_game("alice", "bob", 1.0).unwrap();
+        let (alice, bob) = (*ratings.get("alice").unwrap(), *ratings.get("bob").unwrap());
+        assert_eq!(alice.elo, 1516.0);
+        assert_eq!(bob.elo, 1484.0);
+        assert!(alice.glicko.rating > 1500.0 && bob.glicko.rating < 1500.0);
+        assert!(alice.glicko.deviation < 350.0);
+        assert_eq!((alice.wins, bob.losses), (1, 1));
+
+        // A draw against a weaker player costs rating
+        ratings.record_game("alice", "bob", 0.5).unwrap();
+        assert!(ratings.get("alice").unwrap().elo < alice.elo);
+        assert_eq!(ratings.leaderboard().iter().map(|(name, _)| *name).collect::<Vec<_>>(), vec!["alice", "bob"]);
+
+        assert!(ratings.record_game("carol\tdave", "bob", 0.0).is_err());
+        // Lines starting with # are comments, so these names would be lost when loading the file
+        assert!(matches!(ratings.record_game("#1", "bob", 0.0), Err(RatingsError::InvalidName(_))));
+    }
+
+    #[test]
+    fn ratings_round_trip() {
+        let mut ratings = Ratings::new();
+        ratings.record_game("alice", "bob smith", 0.0).unwrap();
+        ratings.record_game("carol", "alice", 0.5).unwrap();
+        ratings.record_game("dave #2", "alice", 1.0).unwrap();
+
+        let written = ratings.to_string();
+        assert_eq!(written.parse::<Ratings>().unwrap(), ratings);
+        assert!("alice\t1500\t0\t0\t0\t1500\t350".parse::<Ratings>().is_err());
+        assert!("alice\tNaN\t0\t0\t0\t1500\t350\t0.06".parse::<Ratings>().is_err());
+    }
+
+    #[test]
+    fn named_players_keep_their_results_against_unnamed_ones() {
+        let builder = PlayerBuilder::new();
+        let alice = Arc::new(builder.new_named_player("alice", 'x', Box::new(HumanController)).unwrap());
+        let guest = Arc::new(builder.new_player('o', Box::new(HumanController)).unwrap());
+        let win = |player: &Arc<Player>| GameOutcome::Win { player: player.clone(), winning_line: vec![], move_number: 5, team: None };
+
+        let mut ratings = Ratings::new();
+        assert!(ratings.record_outcome(&alice, &guest, &win(&alice)).unwrap());
+        assert!(ratings.record_outcome(&guest, &alice, &win(&guest)).unwrap());
+        assert!(ratings.record_outcome(&guest, &alice, &GameOutcome::Draw).unwrap());
+        assert!(!ratings.record_outcome(&guest, &guest, &GameOutcome::Draw).unwrap());
+
+        // The guest has no rating, so alice's doesn't change
+        let rating = ratings.get("alice").unwrap();
+        assert_eq!((rating.wins, rating.draws, rating.losses), (1, 1, 1));
+        assert_eq!(rating.elo, Rating::INITIAL_ELO);
+        assert_eq!(rating.glicko, Glicko::default());
+        assert_eq!(ratings.leaderboard().len(), 1);
+    }
+}
@@ -0,0 +1,643 @@
+//! A compact text notation for games, so they can be saved, resumed and archived.
+//!
+//! A record is a header of tags followed by the moves, each written as the symbol of the player
+//! and the cell it was placed on:
+//!
+//! ```text
+//! [Symbols "x o"]
+//! [Size "3x3"]
+//! [Variant "3-in-a-row"]
+//! [Result "x"]
+//! [Seed "42"]
+//!
+//! x:b2 o:a1 x:c1 o:a3 x:a2 o:c3 x:b3 o:b1 x:c2
+//! ```
+//!
+//! Columns are lettered from the left (`a` to `z`, then `aa`, `ab`, ...) and rows are numbered
+//! from 1 at the top. The result is the symbol of the winner, `draw`, or `*` for a game that
+//! hasn't finished. The seed is optional, and is the one the game's random choices were made with,
+//! so that games between computer players can be played again. Team games also have a header
+//! listing each team's name and the symbols of its players, such as `[Teams "red=x+ blue=o#"]`.
+//! Teams written without a name, like `x+`, are named after their symbols.
+
+use crate::game::board::{Board, BoardConfig, MoveError};
+use crate::game::cycle::UnsupportedSides;
+use crate::game::player::{Controller, Player, PlayerBuilder, TeamNameError};
+use crate::game::Move;
+
+use std::fmt::{Display, Formatter};
+use std::fmt::Result as FmtResult;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::{fs, io};
+
+/// How a recorded game ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordResult {
+    /// The player with this symbol won
+    Win(char),
+    Draw,
+    /// The game can still be resumed
+    Unfinished,
+}
+
+/// A team of a recorded game, and the symbols of its players
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedTeam {
+    pub name: String,
+    pub symbols: Vec<char>,
+}
+
+impl RecordedTeam {
+    /// Whether a team can be recorded under `name`: names can't be empty, or contain whitespace
+    /// or the `=` that separates them from the symbols
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty() && !name.contains(|c: char| c.is_whitespace() || c == '=')
+    }
+}
+
+/// A mark placed by the player with `symbol`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordedMove {
+    pub symbol: char,
+    pub x_pos: u8,
+    pub y_pos: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    symbols: Vec<char>,
+    teams: Vec<RecordedTeam>,
+    config: BoardConfig,
+    result: RecordResult,
+    seed: Option<u64>,
+    moves: Vec<RecordedMove>,
+}
+
+#[derive(Debug)]
+pub enum RecordError {
+    Io(io::Error),
+    /// A header line that isn't a `[Name "value"]` tag, or has an invalid value
+    InvalidHeader { line: usize, reason: String },
+    MissingHeader(&'static str),
+    /// A move that couldn't be read. Moves are numbered from 1.
+    InvalidMove { number: usize, text: String, reason: String },
+    /// A move by a symbol that isn't in the `Symbols` header
+    UnknownSymbol { number: usize, symbol: char },
+    /// A move by a player when it was somebody else's turn
+    OutOfTurn { number: usize, symbol: char },
+    IllegalMove { number: usize, error: MoveError },
+    /// A move played after the game was already won
+    GameOver { number: usize },
+    /// The moves don't lead to the result in the header
+    ResultMismatch(RecordResult),
+    /// There is no player for one of the symbols in the record
+    MissingPlayer(char),
+    UnsupportedSides(UnsupportedSides),
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            RecordError::Io(error) => write!(f, "Could not access the game record: {}", error),
+            RecordError::InvalidHeader { line, reason } => write!(f, "Invalid header on line {}: {}", line, reason),
+            RecordError::MissingHeader(name) => write!(f, "The game record has no {} header", name),
+            RecordError::InvalidMove { number, text, reason } => {
+                write!(f, "Move {} (\"{}\") is invalid: {}", number, text, reason)
+            },
+            RecordError::UnknownSymbol { number, symbol } => {
+                write!(f, "Move {} is by '{}', who isn't one of the players", number, symbol)
+            },
+            RecordError::OutOfTurn { number, symbol } => write!(f, "Move {} is by '{}' out of turn", number, symbol),
+            RecordError::IllegalMove { number, error } => write!(f, "Move {} is illegal: {}", number, error),
+            RecordError::GameOver { number } => write!(f, "Move {} is played after the game was won", number),
+            RecordError::ResultMismatch(result) => {
+                write!(f, "The moves don't lead to the recorded result \"{}\"", result)
+            },
+            RecordError::MissingPlayer(symbol) => write!(f, "No player uses the recorded symbol '{}'", symbol),
+            RecordError::UnsupportedSides(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RecordError::Io(error) => Some(error),
+            RecordError::IllegalMove { error, .. } => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for RecordError {
+    fn from(error: io::Error) -> Self {
+        RecordError::Io(error)
+    }
+}
+
+impl From<UnsupportedSides> for RecordError {
+    fn from(error: UnsupportedSides) -> Self {
+        RecordError::UnsupportedSides(error)
+    }
+}
+
+impl Display for RecordResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            RecordResult::Win(symbol) => write!(f, "{}", symbol),
+            RecordResult::Draw => write!(f, "draw"),
+            RecordResult::Unfinished => write!(f, "*"),
+        }
+    }
+}
+
+/// The name of the column at `x_pos`: `a` to `z`, then `aa`, `ab`, ...
+pub fn column_name(x_pos: u8) -> String {
+    let mut name = vec![];
+    let mut column = x_pos as u32 + 1;
+    while column > 0 {
+        column -= 1;
+        name.push((b'a' + (column % 26) as u8) as char);
+        column /= 26;
+    }
+    name.iter().rev().collect()
+}
+
+/// The name of a cell, such as `b2` for (1, 1)
+pub fn cell_name(x_pos: u8, y_pos: u8) -> String {
+    format!("{}{}", column_name(x_pos), y_pos as u32 + 1)
+}
+
+/// Reads a cell name written by [`cell_name`](fn.cell_name.html)
+pub fn parse_cell(name: &str) -> Option<(u8, u8)> {
+    let split = name.find(|c: char| !c.is_ascii_lowercase())?;
+    let (letters, digits) = name.split_at(split);
+    if letters.is_empty() || digits.starts_with('0') {
+        return None;
+    }
+
+    let column = letters.bytes().try_fold(0u32, |column, letter| {
+        let column = column.checked_mul(26)?.checked_add((letter - b'a') as u32 + 1)?;
+        Some(column).filter(|&column| column <= 256)
+    })?;
+    let row: u32 = digits.parse().ok()?;
+    if row == 0 || row > 256 {
+        return None;
+    }
+
+    Some(((column - 1) as u8, (row - 1) as u8))
+}
+
+impl Display for RecordedMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}:{}", self.symbol, cell_name(self.x_pos, self.y_pos))
+    }
+}
+
+/// Stands in for the players of a record that is only being checked
+struct Recorded;
+
+impl Controller for Recorded {
+    fn get_next_move(&self, _player: &Arc<Player>, _board: &Board) -> Move {
+        unreachable!("Players of a game record are never asked for moves")
+    }
+}
+
+impl GameRecord {
+    /// An empty record of a game on `config` between players with `symbols`
+    pub fn new(config: BoardConfig, symbols: Vec<char>) -> Self {
+        Self {
+            symbols,
+            teams: vec![],
+            config,
+            result: RecordResult::Unfinished,
+            seed: None,
+            moves: vec![],
+        }
+    }
+
+    /// Records the moves on a board, in the order they were played
+    pub fn from_board(board: &Board, symbols: Vec<char>, result: RecordResult) -> Self {
+        let moves = board.history().iter()
+            .map(|played| RecordedMove {
+                symbol: *played.player.get_symbol(),
+                x_pos: played.x_pos,
+                y_pos: played.y_pos,
+            })
+            .collect();
+
+        Self {
+            symbols,
+            teams: vec![],
+            config: *board.config(),
+            result,
+            seed: None,
+            moves,
+        }
+    }
+
+    pub fn symbols(&self) -> &[char] {
+        &self.symbols
+    }
+
+    /// The teams and their players' symbols, if the game was played in teams
+    pub fn teams(&self) -> &[RecordedTeam] {
+        &self.teams
+    }
+
+    pub fn set_teams(&mut self, teams: Vec<RecordedTeam>) {
+        self.teams = teams;
+    }
+
+    /// Puts the players on the recorded teams, matching them by their symbols. Each team is
+    /// created with `builder` under its recorded name.
+    pub fn assign_teams(&self, builder: &PlayerBuilder, players: &mut [Player]) -> Result<(), TeamNameError> {
+        for recorded in &self.teams {
+            let team = builder.new_team(&recorded.name)?;
+            for player in players.iter_mut().filter(|player| recorded.symbols.contains(player.get_symbol())) {
+                builder.assign_team(player, &team);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn config(&self) -> &BoardConfig {
+        &self.config
+    }
+
+    pub fn result(&self) -> RecordResult {
+        self.result
+    }
+
+    /// The seed the game was played with, if it was recorded
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    pub fn moves(&self) -> &[RecordedMove] {
+        &self.moves
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, RecordError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), RecordError> {
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    /// Plays the recorded moves on a new board, with the player using each symbol placing its marks
+    pub fn replay(&self, players: &[Arc<Player>]) -> Result<Board, RecordError> {
+        let player_of = |symbol: char| players.iter().find(|player| *player.get_symbol() == symbol);
+        if let Some(&symbol) = self.symbols.iter().find(|&&symbol| player_of(symbol).is_none()) {
+            return Err(RecordError::MissingPlayer(symbol));
+        }
+
+        let mut board = Board::with_config(self.config);
+        let mut won = false;
+        for (index, recorded) in self.moves.iter().enumerate() {
+            let number = index + 1;
+            let turn = self.symbols.iter().position(|&symbol| symbol == recorded.symbol)
+                .ok_or(RecordError::UnknownSymbol { number, symbol: recorded.symbol })?;
+            if let Some(previous) = index.checked_sub(1).map(|previous| self.moves[previous].symbol) {
+                if self.symbols[(turn + self.symbols.len() - 1) % self.symbols.len()] != previous {
+                    return Err(RecordError::OutOfTurn { number, symbol: recorded.symbol });
+                }
+            }
+            if won {
+                return Err(RecordError::GameOver { number });
+            }
+
+            let player = player_of(recorded.symbol).expect("Every symbol has a player");
+            won = board.make_move(Move::new(recorded.x_pos, recorded.y_pos, player))
+                .map_err(|error| RecordError::IllegalMove { number, error })?
+                .is_some();
+        }
+
+        let consistent = match self.result {
+            RecordResult::Win(symbol) => board.last_move().filter(|_| won)
+                .is_some_and(|last| *last.player.get_symbol() == symbol),
+            RecordResult::Draw => !won && board.is_full(),
+            RecordResult::Unfinished => !won,
+        };
+        if !consistent {
+            return Err(RecordError::ResultMismatch(self.result));
+        }
+
+        Ok(board)
+    }
+}
+
+/// The headers read so far, with the line each was found on
+#[derive(Default)]
+struct Headers {
+    symbols: Option<Vec<char>>,
+    teams: Option<(usize, Vec<RecordedTeam>)>,
+    size: Option<(u8, u8)>,
+    variant: Option<(usize, u8)>,
+    result: Option<RecordResult>,
+    seed: Option<u64>,
+}
+
+impl Headers {
+    fn parse(&mut self, line: usize, text: &str) -> Result<(), RecordError> {
+        let invalid = |reason: String| RecordError::InvalidHeader { line, reason };
+        let (name, value) = text.strip_prefix('[')
+            .and_then(|text| text.strip_suffix(']'))
+            .and_then(|inner| inner.split_once(' '))
+            .and_then(|(name, value)| Some((name, value.strip_prefix('"')?.strip_suffix('"')?)))
+            .ok_or_else(|| invalid("Headers are written as [Name \"value\"]".to_string()))?;
+
+        match name {
+            "Symbols" => {
+                let mut symbols = vec![];
+                for symbol in value.split_whitespace() {
+                    match single_char(symbol) {
+                        Some(symbol) if symbol != ':' && !symbols.contains(&symbol) => symbols.push(symbol),
+                        _ => return Err(invalid(format!("\"{}\" is not a distinct single character symbol", symbol)))
+                    }
+                }
+                if symbols.len() < 2 {
+                    return Err(invalid("A game needs at least two symbols".to_string()));
+                }
+                self.symbols = Some(symbols);
+            },
+            "Teams" => {
+                let mut teams: Vec<RecordedTeam> = vec![];
+                for team in value.split_whitespace() {
+                    let (name, symbols) = team.split_once('=').unwrap_or((team, team));
+                    let symbols: Vec<char> = symbols.chars().collect();
+                    if !RecordedTeam::is_valid_name(name) || symbols.is_empty() {
+                        return Err(invalid(format!("\"{}\" is not a team like red=x+", team)));
+                    }
+                    if symbols.iter().enumerate().any(|(index, symbol)| {
+                        *symbol == ':' || symbols[..index].contains(symbol)
+                            || teams.iter().flat_map(|other| &other.symbols).any(|other| other == symbol)
+                    }) {
+                        return Err(invalid(format!("\"{}\" repeats a symbol", team)));
+                    }
+                    if teams.iter().any(|other| other.name == name) {
+                        return Err(invalid(format!("There is more than one team named \"{}\"", name)));
+                    }
+                    teams.push(RecordedTeam { name: name.to_string(), symbols });
+                }
+                self.teams = Some((line, teams));
+            },
+            "Size" => {
+                let size = value.split_once('x')
+                    .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+                    .ok_or_else(|| invalid(format!("\"{}\" is not a size like 3x3", value)))?;
+                self.size = Some(size);
+            },
+            "Variant" => {
+                let win_length = value.strip_suffix("-in-a-row")
+                    .and_then(|length| length.parse().ok())
+                    .ok_or_else(|| invalid(format!("\"{}\" is not a variant like 3-in-a-row", value)))?;
+                self.variant = Some((line, win_length));
+            },
+            "Result" => {
+                self.result = Some(match value {
+                    "draw" => RecordResult::Draw,
+                    "*" => RecordResult::Unfinished,
+                    symbol => RecordResult::Win(single_char(symbol)
+                        .ok_or_else(|| invalid(format!("\"{}\" is not a symbol, draw or *", value)))?)
+                });
+            },
+            "Seed" => {
+                let seed = value.parse()
+                    .map_err(|_| invalid(format!("\"{}\" is not a seed between 0 and {}", value, u64::MAX)))?;
+                self.seed = Some(seed);
+            },
+            // Unknown headers are skipped, so records can carry extra information
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn into_record(self) -> Result<GameRecord, RecordError> {
+        let symbols = self.symbols.ok_or(RecordError::MissingHeader("Symbols"))?;
+        let (width, height) = self.size.ok_or(RecordError::MissingHeader("Size"))?;
+        let (line, win_length) = self.variant.ok_or(RecordError::MissingHeader("Variant"))?;
+        let result = self.result.ok_or(RecordError::MissingHeader("Result"))?;
+        let config = BoardConfig::new(width, height, win_length)
+            .map_err(|e| RecordError::InvalidHeader { line, reason: e.to_string() })?;
+        let teams = match self.teams {
+            Some((line, teams)) => {
+                if let Some(symbol) = teams.iter().flat_map(|team| &team.symbols).find(|symbol| !symbols.contains(symbol)) {
+                    let reason = format!("'{}' isn't one of the players' symbols", symbol);
+                    return Err(RecordError::InvalidHeader { line, reason });
+                }
+                teams
+            },
+            None => vec![]
+        };
+
+        Ok(GameRecord {
+            symbols,
+            teams,
+            config,
+            result,
+            seed: self.seed,
+            moves: vec![],
+        })
+    }
+}
+
+fn single_char(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None
+    }
+}
+
+impl Display for GameRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let symbols: Vec<String> = self.symbols.iter().map(char::to_string).collect();
+        writeln!(f, "[Symbols \"{}\"]", symbols.join(" "))?;
+        if !self.teams.is_empty() {
+            // Teams named after their symbols are written without a name
+            let teams: Vec<String> = self.teams.iter()
+                .map(|team| {
+                    let symbols: String = team.symbols.iter().collect();
+                    if team.name == symbols { symbols } else { format!("{}={}", team.name, symbols) }
+                })
+                .collect();
+            writeln!(f, "[Teams \"{}\"]", teams.join(" "))?;
+        }
+        writeln!(f, "[Size \"{}x{}\"]", self.config.width(), self.config.height())?;
+        writeln!(f, "[Variant \"{}-in-a-row\"]", self.config.win_length())?;
+        writeln!(f, "[Result \"{}\"]", self.result)?;
+        if let Some(seed) = self.seed {
+            writeln!(f, "[Seed \"{}\"]", seed)?;
+        }
+        writeln!(f)?;
+
+        let moves: Vec<String> = self.moves.iter().map(RecordedMove::to_string).collect();
+        writeln!(f, "{}", moves.join(" "))
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = RecordError;
+
+    /// Reads a record, checking that every move is legal
+    fn from_str(text: &str) -> Result<Self, RecordError> {
+        let mut headers = Headers::default();
+        let mut lines = text.lines().enumerate().peekable();
+        while let Some((index, line)) = lines.next_if(|(_, line)| line.trim().is_empty() || line.trim().starts_with('[')) {
+            let line = line.trim();
+            if !line.is_empty() {
+                headers.parse(index + 1, line)?;
+            }
+        }
+        let mut record = headers.into_record()?;
+
+        let tokens = lines.flat_map(|(_, line)| line.split_whitespace());
+        for (index, text) in tokens.enumerate() {
+            let invalid = |reason: &str| RecordError::InvalidMove {
+                number: index + 1,
+                text: text.to_string(),
+                reason: reason.to_string(),
+            };
+            let (symbol, cell) = text.split_once(':').ok_or_else(|| invalid("Moves are written as symbol:cell"))?;
+            let symbol = single_char(symbol).ok_or_else(|| invalid("The symbol must be a single character"))?;
+            let (x_pos, y_pos) = parse_cell(cell).ok_or_else(|| invalid("The cell must be a column and row, like b2"))?;
+            record.moves.push(RecordedMove { symbol, x_pos, y_pos });
+        }
+
+        let builder = PlayerBuilder::new();
+        let mut players: Vec<Player> = record.symbols.iter()
+            .map(|&symbol| builder.new_player(symbol, Box::new(Recorded)).expect("Symbols are distinct"))
+            .collect();
+        record.assign_teams(&builder, &mut players).expect("Teams have distinct symbols");
+        record.replay(&players.into_iter().map(Arc::new).collect::<Vec<_>>())?;
+
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FINISHED: &str = "\
+[Symbols \"x o\"]
+[Size \"3x3\"]
+[Variant \"3-in-a-row\"]
+[Result \"x\"]
+
+x:b2 o:a1 x:c1 o:a3
+x:a2 o:c3 x:c2
+";
+
+    #[test]
+    fn cell_names_round_trip() {
+        assert_eq!(cell_name(1, 1), "b2");
+        assert_eq!(column_name(25), "z");
+        assert_eq!(column_name(26), "aa");
+        assert_eq!(column_name(255), "iv");
+        for x in 0..=255 {
+            assert_eq!(parse_cell(&cell_name(x, 255 - x)), Some((x, 255 - x)));
+        }
+        for name in &["b", "2", "b0", "iw1", "a257", "B2", "b2x"] {
+            assert_eq!(parse_cell(name), None, "{} should not parse", name);
+        }
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let record: GameRecord = FINISHED.parse().unwrap();
+        assert_eq!(record.symbols(), &['x', 'o']);
+        assert_eq!(record.result(), RecordResult::Win('x'));
+        assert_eq!(record.moves().len(), 7);
+        assert_eq!(record.moves()[1], RecordedMove { symbol: 'o', x_pos: 0, y_pos: 0 });
+
+        let written = record.to_string();
+        assert_eq!(written.parse::<GameRecord>().unwrap(), record);
+        assert_eq!(record.seed(), None);
+
+        let mut seeded = record.clone();
+        seeded.set_seed(Some(u64::MAX));
+        assert!(seeded.to_string().contains("[Seed \"18446744073709551615\"]"));
+        assert_eq!(seeded.to_string().parse::<GameRecord>().unwrap(), seeded);
+        assert!(written.replace("[Result", "[Seed \"-1\"]\n[Result").parse::<GameRecord>().is_err());
+    }
+
+    #[test]
+    fn team_records_win_with_mixed_lines() {
+        // x and + complete the top row together
+        let text = "\
+[Symbols \"x o + #\"]
+[Teams \"x+ o#\"]
+[Size \"4x4\"]
+[Variant \"3-in-a-row\"]
+[Result \"x\"]
+
+x:a1 o:a2 +:b1 #:d4 x:c1
+";
+        let record: GameRecord = text.parse().unwrap();
+        let symbols: Vec<&[char]> = record.teams().iter().map(|team| team.symbols.as_slice()).collect();
+        assert_eq!(symbols, vec![&['x', '+'][..], &['o', '#'][..]]);
+        assert_eq!(record.teams()[0].name, "x+");
+        assert_eq!(record.to_string(), text);
+
+        // Named teams keep their names
+        let named = text.replace("x+ o#", "red=x+ blue=o#");
+        let record: GameRecord = named.parse().unwrap();
+        assert_eq!(record.teams()[1], RecordedTeam { name: "blue".to_string(), symbols: vec!['o', '#'] });
+        assert_eq!(record.to_string(), named);
+        assert!(text.replace("x+ o#", "=x+ o#").parse::<GameRecord>().is_err());
+        assert!(text.replace("x+ o#", "red=x+ red=o#").parse::<GameRecord>().is_err());
+
+        // Without the teams, nobody has won yet
+        let without_teams = text.replace("[Teams \"x+ o#\"]\n", "");
+        assert!(matches!(without_teams.parse::<GameRecord>(), Err(RecordError::ResultMismatch(_))));
+        assert!(text.replace("x+ o#", "x+ o%").parse::<GameRecord>().is_err());
+    }
+
+    #[test]
+    fn errors_point_at_the_move() {
+        let with_moves = |moves: &str, result: &str| {
+            FINISHED.replace("x:b2 o:a1 x:c1 o:a3\nx:a2 o:c3 x:c2", moves).replace("[Result \"x\"]", result)
+        };
+
+        match with_moves("x:b2 o:a1 x:b2", "[Result \"*\"]").parse::<GameRecord>() {
+            Err(RecordError::IllegalMove { number: 3, error: MoveError::PositionAlreadyFilled(player) }) => {
+                assert_eq!(player.get_symbol(), &'x');
+            },
+            other => panic!("Expected an illegal third move, got {:?}", other)
+        }
+        match with_moves("x:b2 o:d1", "[Result \"*\"]").parse::<GameRecord>() {
+            Err(RecordError::IllegalMove { number: 2, error: MoveError::OutOfBounds(3, 0) }) => {},
+            other => panic!("Expected an out of bounds second move, got {:?}", other)
+        }
+        match with_moves("x:b2 x:a1", "[Result \"*\"]").parse::<GameRecord>() {
+            Err(RecordError::OutOfTurn { number: 2, symbol: 'x' }) => {},
+            other => panic!("Expected the second move to be out of turn, got {:?}", other)
+        }
+        match with_moves("x:b2 o-a1", "[Result \"*\"]").parse::<GameRecord>() {
+            Err(RecordError::InvalidMove { number: 2, .. }) => {},
+            other => panic!("Expected the second move to be invalid, got {:?}", other)
+        }
+        match with_moves("x:b2 o:a1", "[Result \"draw\"]").parse::<GameRecord>() {
+            Err(RecordError::ResultMismatch(RecordResult::Draw)) => {},
+            other => panic!("Expected the result not to match, got {:?}", other)
+        }
+        match with_moves("x:b2", "[Result \"*\"]\n[Teams \"xo ox\"]").parse::<GameRecord>() {
+            Err(RecordError::InvalidHeader { .. }) => {},
+            other => panic!("Expected the teams to be invalid, got {:?}", other)
+        }
+        match with_moves("x:b2", "").parse::<GameRecord>() {
+            Err(RecordError::MissingHeader("Result")) => {},
+            other => panic!("Expected the result header to be missing, got {:?}", other)
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::game::board::{Board, BoardConfig};
+use crate::game::profile;
+
+use crossterm::{execute, queue};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+
+use std::io::{stdout, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static PANIC_HOOK: Once = Once::new();
+
+/// Whether a [`TerminalGuard`](struct.TerminalGuard.html) currently owns the terminal, meaning
+/// the board should be drawn in place instead of printed
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::SeqCst)
+}
+
+/// Switches the terminal to a full-screen, raw mode session for as long as it is alive.
+/// The terminal is restored when the guard is dropped or if the program panics.
+pub struct TerminalGuard(());
+
+impl TerminalGuard {
+    pub fn new() -> crossterm::Result<Self> {
+        PANIC_HOOK.call_once(|| {
+            let previous = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                if is_active() {
+                    restore_terminal();
+                }
+                previous(info);
+            }));
+        });
+
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen, EnableMouseCapture, Hide)?;
+        ACTIVE.store(true, Ordering::SeqCst);
+        Ok(TerminalGuard(()))
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+fn restore_terminal() {
+    ACTIVE.store(false, Ordering::SeqCst);
+    let _ = execute!(stdout(), Show, DisableMouseCapture, LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+}
+
+/// Where the parts of a board are drawn on the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    config: BoardConfig,
+}
+
+impl Layout {
+    /// The row the status line is drawn on
+    pub const STATUS_ROW: u16 = 0;
+    /// The top left corner of the grid
+    pub const ORIGIN: (u16, u16) = (2, 2);
+    pub const CELL_WIDTH: u16 = 4;
+    pub const CELL_HEIGHT: u16 = 2;
+
+    pub fn new(config: BoardConfig) -> Self {
+        Self { config }
+    }
+
+    /// The screen column and row the mark at (x_pos, y_pos) is drawn at
+    pub fn cell_to_screen(&self, x_pos: u8, y_pos: u8) -> (u16, u16) {
+        (
+            Self::ORIGIN.0 + x_pos as u16 * Self::CELL_WIDTH + Self::CELL_WIDTH / 2,
+            Self::ORIGIN.1 + y_pos as u16 * Self::CELL_HEIGHT + 1,
+        )
+    }
+
+    /// The cell drawn at a screen column and row, if there is one. Grid lines don't belong to any cell.
+    pub fn screen_to_cell(&self, column: u16, row: u16) -> Option<(u8, u8)> {
+        let column = column.checked_sub(Self::ORIGIN.0)?;
+        let row = row.checked_sub(Self::ORIGIN.1)?;
+        if column % Self::CELL_WIDTH == 0 || row % Self::CELL_HEIGHT == 0 {
+            return None;
+        }
+
+        let x = column / Self::CELL_WIDTH;
+        let y = row / Self::CELL_HEIGHT;
+        if x < self.config.width() as u16 && y < self.config.height() as u16 {
+            Some((x as u8, y as u8))
+        } else {
+            None
+        }
+    }
+
+    /// The row below the grid, where messages such as the controls are drawn
+    pub fn footer_row(&self) -> u16 {
+        Self::ORIGIN.1 + self.config.height() as u16 * Self::CELL_HEIGHT + 2
+    }
+
+    /// The line drawn above and below every row of cells
+    fn border(&self) -> String {
+        let mut line = String::from("+");
+        for _ in 0..self.config.width() {
+            line.push_str("---+");
+        }
+        line
+    }
+}
+
+/// Redraws the whole screen with the board, a status line above it, and a footer below it.
+/// The cell under `cursor` is highlighted.
+pub fn draw(board: &Board, status: &str, footer: &str, cursor: Option<(u8, u8)>) -> crossterm::Result<()> {
+    let config = board.config();
+    let layout = Layout::new(*config);
+    let mut stdout = stdout();
+
+    queue!(
+        stdout,
+        Clear(ClearType::All),
+        MoveTo(0, Layout::STATUS_ROW),
+        SetAttribute(Attribute::Bold),
+        Print(status),
+        SetAttribute(Attribute::Reset)
+    )?;
+
+    let (left, top) = Layout::ORIGIN;
+    queue!(stdout, MoveTo(left, top), Print(layout.border()))?;
+    for y in 0..config.height() {
+        let row = top + y as u16 * Layout::CELL_HEIGHT + 1;
+        queue!(stdout, MoveTo(left, row), Print('|'))?;
+        for x in 0..config.width() {
+            let (mark, color) = match board.get_at_pos(x, y) {
+                Ok(Some(player)) => (*player.get_symbol(), player.color()),
+                _ => (' ', None)
+            };
+            if cursor == Some((x, y)) {
+                queue!(stdout, SetAttribute(Attribute::Reverse))?;
+            }
+            if let Some(color) = color {
+                queue!(stdout, SetForegroundColor(terminal_color(color)))?;
+            }
+            queue!(stdout, Print(format!(" {} ", mark)), ResetColor, SetAttribute(Attribute::Reset), Print('|'))?;
+        }
+        queue!(stdout, MoveTo(left, row + 1), Print(layout.border()))?;
+    }
+
+    queue!(stdout, MoveTo(0, layout.footer_row()), Print(footer))?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn terminal_color(color: profile::Color) -> Color {
+    match color {
+        profile::Color::Red => Color::Red,
+        profile::Color::Green => Color::Green,
+        profile::Color::Yellow => Color::Yellow,
+        profile::Color::Blue => Color::Blue,
+        profile::Color::Magenta => Color::Magenta,
+        profile::Color::Cyan => Color::Cyan,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cells_are_drawn_inside_the_grid() {
+        let layout = Layout::new(BoardConfig::new(4, 3, 3).unwrap());
+
+        assert_eq!(layout.cell_to_screen(0, 0), (4, 3));
+        assert_eq!(layout.cell_to_screen(3, 2), (16, 7));
+        assert_eq!(layout.border(), "+---+---+---+---+");
+        assert_eq!(layout.footer_row(), 10);
+    }
+
+    #[test]
+    fn screen_positions_map_back_to_cells() {
+        let layout = Layout::new(BoardConfig::new(4, 3, 3).unwrap());
+
+        for y in 0..3 {
+            for x in 0..4 {
+                let (column, row) = layout.cell_to_screen(x, y);
+                assert_eq!(layout.screen_to_cell(column, row), Some((x, y)));
+                assert_eq!(layout.screen_to_cell(column - 1, row), Some((x, y)));
+                assert_eq!(layout.screen_to_cell(column + 1, row), Some((x, y)));
+            }
+        }
+
+        // Grid lines
+        assert_eq!(layout.screen_to_cell(2, 3), None);
+        assert_eq!(layout.screen_to_cell(4, 2), None);
+        assert_eq!(layout.screen_to_cell(4, 4), None);
+        // Outside the grid
+        assert_eq!(layout.screen_to_cell(0, 0), None);
+        assert_eq!(layout.screen_to_cell(19, 3), None);
+        assert_eq!(layout.screen_to_cell(4, 9), None);
+    }
+}
@@ -0,0 +1,276 @@
+//! Series of games between the same two players, such as the best-of-5 matches played at the office
+
+use crate::game::cycle::{CurrentPlayer, GameCycle, GameOutcome, StartingRule};
+use crate::game::player::Player;
+use crate::game::render;
+
+use crossterm::event::{self, Event};
+use std::fmt::{Display, Formatter};
+use std::fmt::Result as FmtResult;
+use std::sync::Arc;
+
+/// The games won by each player and drawn so far
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub player1_wins: u32,
+    pub player2_wins: u32,
+    pub draws: u32,
+}
+
+impl Score {
+    pub fn games(&self) -> u32 {
+        self.player1_wins + self.player2_wins + self.draws
+    }
+
+    fn wins(&self, player: CurrentPlayer) -> u32 {
+        if player == CurrentPlayer::PLAYER1 {
+            self.player1_wins
+        } else {
+            self.player2_wins
+        }
+    }
+}
+
+/// A game played as part of a match
+#[derive(Debug)]
+pub struct MatchGame {
+    pub starter: Arc<Player>,
+    pub outcome: GameOutcome,
+}
+
+/// A best-of-N match. Players take turns moving first unless the cycle's starting rule is
+/// changed, and the match ends as soon as one player has won more games than the other could
+/// still catch up on.
+pub struct Match {
+    cycle: GameCycle,
+    best_of: u32,
+    score: Score,
+    games: Vec<MatchGame>,
+}
+
+/// How a match ended
+#[derive(Debug)]
+pub struct MatchSummary {
+    /// The player with the most wins, if they didn't tie
+    pub winner: Option<Arc<Player>>,
+    pub player1: Arc<Player>,
+    pub player2: Arc<Player>,
+    pub score: Score,
+    pub best_of: u32,
+    /// Why the match was abandoned, if a game was
+    pub aborted: Option<String>,
+}
+
+impl Match {
+    /// A match of up to `best_of` games between the players of `cycle`, on its board
+    ///
+    /// # Panics
+    /// If the cycle doesn't have exactly two players
+    pub fn new(mut cycle: GameCycle, best_of: u32) -> Self {
+        assert_eq!(cycle.players().len(), 2, "A match is played between two players");
+        cycle.set_starting_rule(StartingRule::Alternate).expect("Alternating works for any players");
+        Self {
+            cycle,
+            best_of: best_of.max(1),
+            score: Score::default(),
+            games: vec![],
+        }
+    }
+
+    /// The cycle the games are played with, to change its display, seed or starting rule
+    pub fn cycle_mut(&mut self) -> &mut GameCycle {
+        &mut self.cycle
+    }
+
+    pub fn best_of(&self) -> u32 {
+        self.best_of
+    }
+
+    pub fn score(&self) -> &Score {
+        &self.score
+    }
+
+    pub fn games(&self) -> &[MatchGame] {
+        &self.games
+    }
+
+    /// The player who can no longer be caught, if there is one
+    pub fn clinched(&self) -> Option<&Arc<Player>> {
+        let remaining = self.best_of - self.score.games();
+        [CurrentPlayer::PLAYER1, CurrentPlayer::PLAYER2].iter()
+            .find(|&&player| self.score.wins(player) > self.score.wins(player.next(2)) + remaining)
+            .map(|&player| self.cycle.player(player))
+    }
+
+    /// Whether the match has been clinched, every game has been played, or a game was abandoned
+    pub fn is_over(&self) -> bool {
+        self.score.games() >= self.best_of || self.clinched().is_some() || self.aborted().is_some()
+    }
+
+    /// Plays the next game, unless the match is over
+    pub fn play_game(&mut self) -> Option<&MatchGame> {
+        if self.is_over() {
+            return None;
+        }
+
+        self.cycle.new_game();
+        let outcome = self.cycle.cycle();
+        match outcome.winner() {
+            Some(winner) if winner == self.cycle.player1() => self.score.player1_wins += 1,
+            Some(_) => self.score.player2_wins += 1,
+            None if matches!(outcome, GameOutcome::Draw) => self.score.draws += 1,
+            None => {}
+        }
+        let starter = self.cycle.board().history().first()
+            .map(|first_move| first_move.player.clone())
+            .unwrap_or_else(|| self.cycle.player1().clone());
+        self.games.push(MatchGame { starter, outcome });
+
+        self.show_score();
+        self.games.last()
+    }
+
+    /// Plays games until the match is over
+    pub fn play(&mut self) -> MatchSummary {
+        while self.play_game().is_some() {}
+        self.summary()
+    }
+
+    pub fn summary(&self) -> MatchSummary {
+        let winner = match self.score.player1_wins.cmp(&self.score.player2_wins) {
+            std::cmp::Ordering::Greater => Some(self.cycle.player1().clone()),
+            std::cmp::Ordering::Less => Some(self.cycle.player2().clone()),
+            std::cmp::Ordering::Equal => None
+        };
+
+        MatchSummary {
+            winner,
+            player1: self.cycle.player1().clone(),
+            player2: self.cycle.player2().clone(),
+            score: self.score,
+            best_of: self.best_of,
+            aborted: self.aborted().map(str::to_string),
+        }
+    }
+
+    fn aborted(&self) -> Option<&str> {
+        self.games.iter().find_map(|game| match &game.outcome {
+            GameOutcome::Aborted { reason } => Some(reason.as_str()),
+            _ => None
+        })
+    }
+
+    /// Shows the last game's result and the running score, if the cycle displays its games. While
+    /// the board is drawn full-screen, the result stays up until a key is pressed.
+    fn show_score(&self) {
+        let game = match self.games.last() {
+            Some(game) => game,
+            None => return
+        };
+        if !self.cycle.displays() {
+            return;
+        }
+
+        let result = format!("Game {}: {}", self.games.len(), game.outcome);
+        let score = format!("Score: {}", score_line(self.cycle.player1(), self.cycle.player2(), &self.score));
+        if render::is_active() {
+            let footer = format!("{}   Press any key to continue", score);
+            render::draw(self.cycle.board(), &result, &footer, None).expect("Could not draw the board");
+            while !matches!(event::read(), Ok(Event::Key(_)) | Err(_)) {}
+        } else {
+            println!("{}", self.cycle.board());
+            println!("{}", result);
+            println!("{}", score);
+        }
+    }
+}
+
+/// The score with each player's symbol, such as `x 2 - 1 o, 1 draw`
+fn score_line(player1: &Player, player2: &Player, score: &Score) -> String {
+    let draws = if score.draws == 1 { "draw" } else { "draws" };
+    format!(
+        "{} {} - {} {}, {} {}",
+        player1.get_symbol(), score.player1_wins, score.player2_wins, player2.get_symbol(), score.draws, draws
+    )
+}
+
+impl Display for MatchSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let score = score_line(&self.player1, &self.player2, &self.score);
+        let games = self.score.games();
+        match (&self.aborted, &self.winner) {
+            (Some(reason), _) => write!(f, "The match was abandoned at {} after {} games: {}", score, games, reason),
+            (None, Some(winner)) => write!(f, "{} wins the best-of-{} match {} in {} games", winner, self.best_of, score, games),
+            (None, None) => write!(f, "The best-of-{} match is tied {} in {} games", self.best_of, score, games),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::board::Board;
+    use crate::game::player::{Controller, PlayerBuilder};
+    use crate::game::player::controllers::minimax::MinimaxController;
+    use crate::game::player::controllers::random::RandomController;
+    use crate::game::Move;
+
+    /// Fills in the first open position, which never stops a perfect player from winning
+    struct FirstOpen;
+
+    impl Controller for FirstOpen {
+        fn get_next_move(&self, player: &Arc<Player>, board: &Board) -> Move {
+            let (x, y) = board.get_open_positions()[0];
+            Move::new(x, y, player)
+        }
+    }
+
+    fn new_match(player1: Box<dyn Controller>, player2: Box<dyn Controller>, best_of: u32) -> Match {
+        let builder = PlayerBuilder::with_seed(1);
+        let x = builder.new_player('x', player1).unwrap();
+        let o = builder.new_player('o', player2).unwrap();
+        let mut cycle = GameCycle::new(x, o);
+        cycle.set_display(false);
+        cycle.set_seed(1);
+        Match::new(cycle, best_of)
+    }
+
+    #[test]
+    fn matches_end_once_clinched() {
+        let mut series = new_match(MinimaxController::new().into(), FirstOpen.into(), 5);
+        let summary = series.play();
+
+        assert_eq!(summary.score, Score { player1_wins: 3, player2_wins: 0, draws: 0 });
+        assert_eq!(series.games().len(), 3);
+        assert_eq!(summary.winner.as_ref(), Some(series.cycle_mut().player1()));
+        assert!(series.play_game().is_none());
+
+        // Players take turns moving first
+        let starters: Vec<char> = series.games().iter().map(|game| *game.starter.get_symbol()).collect();
+        assert_ne!(starters[0], starters[1]);
+        assert_eq!(starters[0], starters[2]);
+    }
+
+    #[test]
+    fn drawn_games_count_towards_the_match() {
+        let mut series = new_match(MinimaxController::new().into(), MinimaxController::new().into(), 4);
+        let summary = series.play();
+
+        assert_eq!(summary.score, Score { player1_wins: 0, player2_wins: 0, draws: 4 });
+        assert_eq!(summary.winner, None);
+        assert_eq!(summary.to_string(), "The best-of-4 match is tied x 0 - 0 o, 4 draws in 4 games");
+    }
+
+    #[test]
+    fn running_scores_add_up() {
+        let mut series = new_match(RandomController::with_seed(5).into(), RandomController::with_seed(6).into(), 7);
+        while let Some(game) = series.play_game() {
+            assert!(!matches!(game.outcome, GameOutcome::Aborted { .. }));
+        }
+
+        let score = *series.score();
+        assert_eq!(score.games() as usize, series.games().len());
+        assert!(score.games() <= 7);
+        assert!(series.clinched().is_some() || score.games() == 7);
+    }
+}
@@ -0,0 +1,318 @@
+//! Solves games by walking every reachable position, which is only feasible on small boards such
+//! as the standard 3×3 one.
+
+use crate::game::board::{Board, BoardConfig};
+use crate::game::board::bitboard::BitBoard;
+use crate::game::board::position::Seat;
+use crate::game::board::symmetry::Symmetries;
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::fmt::Result as FmtResult;
+
+/// The most cells a board can have for [`solve`](fn.solve.html) to finish in reasonable time and
+/// memory. Every reachable position is kept in the table: a 4×3 board has about a hundred thousand
+/// of them, while a 4×4 board has almost ten million, which takes about a gigabyte.
+pub const MAX_CELLS: usize = 12;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TooManyCells(usize);
+
+impl Display for TooManyCells {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "A board with {} cells has too many positions to solve, the solver handles at most {}", self.0, MAX_CELLS)
+    }
+}
+
+impl std::error::Error for TooManyCells {}
+
+/// The game-theoretic value of a position for the player to move, with perfect play from both sides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    /// The player to move wins, with the game ending this many moves from now
+    Win(u8),
+    Draw,
+    /// The player to move loses, with the game ending this many moves from now. A position that
+    /// has already been lost is a loss in 0.
+    Loss(u8),
+}
+
+impl Value {
+    /// The value of the position before the move that led here, for the player who made it
+    pub fn for_previous_player(self) -> Self {
+        match self {
+            Value::Win(moves) => Value::Loss(moves + 1),
+            Value::Draw => Value::Draw,
+            Value::Loss(moves) => Value::Win(moves + 1),
+        }
+    }
+
+    /// Faster wins and slower losses score higher
+    fn score(self) -> i32 {
+        match self {
+            Value::Win(moves) => 1000 - moves as i32,
+            Value::Draw => 0,
+            Value::Loss(moves) => moves as i32 - 1000,
+        }
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score().cmp(&other.score())
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Value::Win(moves) => write!(f, "+{}", moves),
+            Value::Draw => write!(f, "="),
+            Value::Loss(moves) => write!(f, "-{}", moves),
+        }
+    }
+}
+
+/// What the solver knows about a reachable position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Solved {
+    pub value: Value,
+    /// A mask of the cells that achieve the value, in the same layout as a
+    /// [`BitBoard`](../board/bitboard/struct.BitBoard.html). Empty once the game is over.
+    pub best_moves: u128,
+}
+
+/// Counts of the positions reachable from the solved position, including itself
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub positions: usize,
+    /// Finished positions won by each seat, where seat 0 is the player to move in the solved position
+    pub wins: [usize; 2],
+    pub draws: usize,
+}
+
+impl Stats {
+    pub fn terminal_positions(&self) -> usize {
+        self.wins[0] + self.wins[1] + self.draws
+    }
+}
+
+/// The result of solving a position: its value, and the value of every position reachable from it
+pub struct Evaluation {
+    root: BitBoard,
+    table: HashMap<(u128, u128), Solved>,
+    stats: Stats,
+}
+
+impl Evaluation {
+    /// The value of the solved position for the player to move
+    pub fn value(&self) -> Value {
+        self.root_entry().value
+    }
+
+    /// Every move achieving the value of the solved position, as (x, y) positions
+    pub fn best_moves(&self) -> Vec<(u8, u8)> {
+        let config = self.root.config();
+        BitBoard::cells_of(self.root_entry().best_moves)
+            .map(|index| config.position_of(index))
+            .collect()
+    }
+
+    /// The value of every open position, for the player to move, in row-major order
+    pub fn move_values(&self) -> Vec<((u8, u8), Value)> {
+        let config = self.root.config();
+        self.root.open_cells()
+            .map(|index| {
+                let mut child = self.root.clone();
+                child.play(index);
+                let value = self.get(&child).expect("Every child of the root is solved").value;
+                (config.position_of(index), value.for_previous_player())
+            })
+            .collect()
+    }
+
+    /// Looks up a position reachable from the solved one. Seats are numbered as in the solved
+    /// position, where seat 0 was the player to move.
+    pub fn get(&self, position: &BitBoard) -> Option<&Solved> {
+        self.table.get(&(position.seat_mask(0), position.seat_mask(1)))
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    fn root_entry(&self) -> &Solved {
+        self.get(&self.root).expect("The root is always solved")
+    }
+}
+
+/// Solves `board` for the player who didn't play the last move by walking every position that
+/// can be reached from it. Positions reached through different move orders are only solved once.
+/// Boards with more than [`MAX_CELLS`](constant.MAX_CELLS.html) cells are refused, as solving them
+/// would take too long and too much memory.
+pub fn solve(board: &Board) -> Result<Evaluation, TooManyCells> {
+    let size = board.config().size();
+    if size > MAX_CELLS {
+        return Err(TooManyCells(size));
+    }
+
+    let mut root = BitBoard::after_last_move(board).expect("Boards the solver accepts fit in a bitboard");
+    let mut evaluation = Evaluation {
+        root: root.clone(),
+        table: HashMap::new(),
+        stats: Stats::default(),
+    };
+
+    let finished = board.check_winner().is_some();
+    visit(&mut evaluation, &mut root, finished);
+    evaluation.stats.positions = evaluation.table.len();
+    Ok(evaluation)
+}
+
+/// Lists every position that can be reached from an empty board, including the empty board and
+/// finished games. With `merge_symmetric`, positions that are rotations or reflections of each
+/// other are only listed once, by their canonical form.
+///
+/// # Panics
+///
+/// If the board has more cells than a [`BitBoard`](../board/bitboard/struct.BitBoard.html) can hold
+pub fn reachable_positions(config: BoardConfig, merge_symmetric: bool) -> Vec<BitBoard> {
+    let symmetries = if merge_symmetric { Some(Symmetries::new(&config)) } else { None };
+    let canonical = |position: BitBoard| match &symmetries {
+        Some(symmetries) => symmetries.canonical_form(&position).0,
+        None => position
+    };
+
+    let root = canonical(BitBoard::new(config).expect("The board is too large to enumerate"));
+    let mut seen = HashSet::new();
+    seen.insert((root.seat_mask(0), root.seat_mask(1)));
+    let mut positions = vec![root];
+
+    // Every position in the list is expanded once, with new positions appended as they are found
+    let mut next = 0;
+    while let Some(position) = positions.get(next).cloned() {
+        next += 1;
+        if position.winner().is_some() {
+            continue;
+        }
+
+        for index in position.open_cells() {
+            let mut child = position.clone();
+            child.play(index);
+            let child = canonical(child);
+            if seen.insert((child.seat_mask(0), child.seat_mask(1))) {
+                positions.push(child);
+            }
+        }
+    }
+
+    positions
+}
+
+/// Solves `position`, where `finished` is whether the last move won the game
+fn visit(evaluation: &mut Evaluation, position: &mut BitBoard, finished: bool) -> Value {
+    let key = (position.seat_mask(0), position.seat_mask(1));
+    if let Some(solved) = evaluation.table.get(&key) {
+        return solved.value;
+    }
+
+    let solved = if finished {
+        let winner: Seat = 1 - position.to_move();
+        evaluation.stats.wins[winner as usize] += 1;
+        Solved { value: Value::Loss(0), best_moves: 0 }
+    } else if position.is_full() {
+        evaluation.stats.draws += 1;
+        Solved { value: Value::Draw, best_moves: 0 }
+    } else {
+        let mut best: Option<Solved> = None;
+        for index in position.open_cells() {
+            let won = position.play(index);
+            let value = visit(evaluation, position, won).for_previous_player();
+            position.undo(index);
+
+            match best.as_mut() {
+                Some(best) if value == best.value => best.best_moves |= 1 << index,
+                Some(best) if value < best.value => {},
+                _ => best = Some(Solved { value, best_moves: 1 << index })
+            }
+        }
+        best.expect("A position that isn't full has an open cell")
+    };
+
+    evaluation.table.insert(key, solved);
+    solved.value
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::board::BoardConfig;
+    use crate::game::player::PlayerBuilder;
+    use crate::game::player::controllers::HumanController;
+    use crate::game::Move;
+    use std::sync::Arc;
+
+    #[test]
+    fn standard_board_has_known_counts() {
+        let evaluation = solve(&Board::new()).unwrap();
+        let stats = evaluation.stats();
+        assert_eq!(stats.positions, 5478);
+        assert_eq!(stats.wins, [626, 316]);
+        assert_eq!(stats.draws, 16);
+        assert_eq!(evaluation.value(), Value::Draw);
+        assert_eq!(evaluation.best_moves().len(), 9);
+        assert!(evaluation.move_values().iter().all(|&(_, value)| value == Value::Draw));
+    }
+
+    #[test]
+    fn symmetric_positions_are_merged() {
+        assert_eq!(reachable_positions(BoardConfig::default(), false).len(), 5478);
+        assert_eq!(reachable_positions(BoardConfig::default(), true).len(), 765);
+        // One empty board, and the corner, edge and center openings
+        let small = reachable_positions(BoardConfig::square(3, 1).unwrap(), true);
+        assert_eq!(small.len(), 4);
+    }
+
+    #[test]
+    fn solves_positions_part_way_through() {
+        let builder = PlayerBuilder::new();
+        let x = Arc::new(builder.new_player('x', HumanController.into()).unwrap());
+        let o = Arc::new(builder.new_player('o', HumanController.into()).unwrap());
+
+        // x . .
+        // . o .
+        // . . x
+        // o must take an edge, as taking a corner lets x fork
+        let mut board = Board::new();
+        for &(x_pos, y_pos, player) in &[(0, 0, &x), (1, 1, &o), (2, 2, &x)] {
+            board.make_move(Move::new(x_pos, y_pos, player)).unwrap();
+        }
+
+        let evaluation = solve(&board).unwrap();
+        assert_eq!(evaluation.value(), Value::Draw);
+        assert_eq!(evaluation.best_moves(), vec![(1, 0), (0, 1), (2, 1), (1, 2)]);
+        assert!(evaluation.move_values().contains(&((2, 0), Value::Loss(4))));
+    }
+
+    #[test]
+    fn small_boards_are_won_by_the_first_player() {
+        let board = Board::with_config(BoardConfig::new(3, 2, 2).unwrap());
+        let evaluation = solve(&board).unwrap();
+        assert_eq!(evaluation.value(), Value::Win(3));
+        assert_eq!(evaluation.best_moves().len(), 6);
+    }
+
+    #[test]
+    fn large_boards_are_refused() {
+        assert_eq!(solve(&Board::with_config(BoardConfig::square(4, 3).unwrap())).err(), Some(TooManyCells(16)));
+        assert_eq!(solve(&Board::with_config(BoardConfig::square(5, 4).unwrap())).err(), Some(TooManyCells(25)));
+        assert_eq!(solve(&Board::with_config(BoardConfig::square(12, 5).unwrap())).err(), Some(TooManyCells(144)));
+    }
+}
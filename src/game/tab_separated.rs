@@ -0,0 +1,78 @@
+//! Files with an entry on every line and its fields separated by tabs, as used for the
+//! [`Ratings`](../rating/struct.Ratings.html) and [`Profiles`](../profile/struct.Profiles.html).
+//! The first field is a name. Blank lines and lines starting with `#` are skipped, and files
+//! start with a comment naming the fields.
+
+use std::fmt::{Formatter, Write};
+use std::fmt::Result as FmtResult;
+use std::path::Path;
+use std::{fs, io};
+
+/// Whether `name` can be the first field of a line: names can't be empty, start with the `#` of
+/// a comment, or contain tabs or line breaks
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('#') && !name.contains(['\t', '\n', '\r'])
+}
+
+/// Reads the file at `path`, which is empty if it doesn't exist yet
+pub(crate) fn read<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(text),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(error) => Err(error)
+    }
+}
+
+/// Writes the comment naming the fields
+pub(crate) fn write_header(f: &mut Formatter<'_>, fields: &[&str]) -> FmtResult {
+    f.write_char('#')?;
+    for (index, field) in fields.iter().enumerate() {
+        f.write_char(if index == 0 { ' ' } else { '\t' })?;
+        f.write_str(field)?;
+    }
+    f.write_char('\n')
+}
+
+/// Calls `parse_line` with the fields of every entry in `text`, which must have one field for
+/// each of `fields`. Fails with the line number, counting from 1, and the reason for the first
+/// line that is invalid.
+pub(crate) fn parse<F>(text: &str, fields: &[&str], mut parse_line: F) -> Result<(), (usize, String)>
+where
+    F: FnMut(&[&str]) -> Result<(), String>
+{
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let values: Vec<&str> = line.split('\t').collect();
+        let result = if values.len() == fields.len() {
+            parse_line(&values)
+        } else {
+            Err(format!("Expected the {} separated by tabs", fields.join(", ")))
+        };
+        result.map_err(|reason| (index + 1, reason))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        let text = "# name\tcount\n\nalice\t1\n#bob\t2\ncarol\t3\n";
+        let mut entries = vec![];
+        parse(text, &["name", "count"], |fields| {
+            entries.push((fields[0].to_string(), fields[1].to_string()));
+            Ok(())
+        }).unwrap();
+        assert_eq!(entries, vec![("alice".to_string(), "1".to_string()), ("carol".to_string(), "3".to_string())]);
+
+        let error = parse("alice\t1\nbob", &["name", "count"], |_| Ok(())).unwrap_err();
+        assert_eq!(error.0, 2);
+        assert!(!is_valid_name("#bob"));
+        assert!(is_valid_name("bob #2"));
+    }
+}
@@ -0,0 +1,476 @@
+//! Tournaments between controllers, to compare them over many games. Every pairing plays both
+//! ways, so each entrant moves first equally often against the other.
+
+use crate::game::batch::run_parallel;
+use crate::game::board::BoardConfig;
+use crate::game::cycle::{CurrentPlayer, GameCycle, GameOutcome};
+use crate::game::player::PlayerBuilder;
+use crate::game::player::controllers::ControllerKind;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::fmt::Result as FmtResult;
+
+/// A controller taking part in a tournament, known by a name that is unique within it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entrant {
+    pub name: String,
+    pub kind: ControllerKind,
+}
+
+impl Entrant {
+    /// An entrant named after its controller, such as `minimax:4`
+    pub fn new(kind: ControllerKind) -> Self {
+        Self {
+            name: kind.to_string(),
+            kind,
+        }
+    }
+}
+
+/// Which entrants play each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Every entrant plays every other
+    RoundRobin,
+    /// Entrants with similar scores are paired for a number of rounds, avoiding rematches where
+    /// possible. With an odd number of entrants, a different entrant sits out every round and is
+    /// scored as if it had won its games.
+    Swiss { rounds: u32 },
+}
+
+impl Format {
+    /// Enough Swiss rounds to separate `entrants` entrants, the base 2 logarithm rounded up
+    pub fn swiss_for(entrants: usize) -> Self {
+        let rounds = entrants.max(2).next_power_of_two().trailing_zeros();
+        Format::Swiss { rounds }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TournamentError {
+    TooFewEntrants,
+    DuplicateName(String),
+}
+
+impl Display for TournamentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            TournamentError::TooFewEntrants => write!(f, "A tournament needs at least two entrants"),
+            TournamentError::DuplicateName(name) => write!(f, "More than one entrant is named \"{}\"", name),
+        }
+    }
+}
+
+impl std::error::Error for TournamentError {}
+
+/// Games won, drawn and lost
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Tally {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Tally {
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+}
+
+/// An entrant's results over the whole tournament
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    pub tally: Tally,
+    /// Rounds sat out in a Swiss tournament
+    pub byes: u32,
+    /// A point for every win and half a point for every draw, including the wins byes count as
+    pub points: f64,
+}
+
+/// The final standings, best first, with the results of every pairing
+#[derive(Debug, Clone)]
+pub struct Standings {
+    pub standings: Vec<Standing>,
+    /// The results of the entrant in each row against the entrant in each column, in the order
+    /// the entrants were given
+    pub head_to_head: Vec<Vec<Tally>>,
+    pub names: Vec<String>,
+    /// Games that were abandoned, which count for neither entrant
+    pub aborted: u32,
+}
+
+/// A game between two entrants, with `home` moving first
+#[derive(Debug, Clone, Copy)]
+struct Fixture {
+    home: usize,
+    away: usize,
+    seed: u64,
+}
+
+pub struct Tournament {
+    entrants: Vec<Entrant>,
+    format: Format,
+    config: BoardConfig,
+    games_per_side: u32,
+    threads: usize,
+    seed: Option<u64>,
+}
+
+impl Tournament {
+    pub fn new(entrants: Vec<Entrant>, format: Format) -> Result<Self, TournamentError> {
+        if entrants.len() < 2 {
+            return Err(TournamentError::TooFewEntrants);
+        }
+        let mut names = HashSet::new();
+        if let Some(duplicate) = entrants.iter().find(|entrant| !names.insert(&entrant.name)) {
+            return Err(TournamentError::DuplicateName(duplicate.name.clone()));
+        }
+
+        Ok(Self {
+            entrants,
+            format,
+            config: BoardConfig::default(),
+            games_per_side: 1,
+            threads: 1,
+            seed: None,
+        })
+    }
+
+    pub fn set_config(&mut self, config: BoardConfig) {
+        self.config = config;
+    }
+
+    /// Sets how many games each entrant of a pairing moves first in
+    pub fn set_games_per_side(&mut self, games: u32) {
+        self.games_per_side = games.max(1);
+    }
+
+    /// Sets how many games are played at the same time
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Seeds every game, so the tournament has the same results every time it is run with
+    /// reproducible controllers, however many threads play it
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
+
+    pub fn entrants(&self) -> &[Entrant] {
+        &self.entrants
+    }
+
+    /// Plays every game of the tournament
+    pub fn run(&self) -> Standings {
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy()
+        };
+        let count = self.entrants.len();
+        let mut results = Results::new(count);
+
+        match self.format {
+            Format::RoundRobin => {
+                let pairings: Vec<(usize, usize)> = (0..count)
+                    .flat_map(|first| (first + 1..count).map(move |second| (first, second)))
+                    .collect();
+                self.play_round(&pairings, &mut rng, &mut results);
+            },
+            Format::Swiss { rounds } => {
+                for _ in 0..rounds {
+                    let (pairings, bye) = results.swiss_pairings();
+                    if let Some(bye) = bye {
+                        results.byes[bye] += 1;
+                    }
+                    self.play_round(&pairings, &mut rng, &mut results);
+                }
+            }
+        }
+
+        results.into_standings(&self.entrants, 2 * self.games_per_side)
+    }
+
+    /// Plays every pairing both ways, in parallel
+    fn play_round<R: Rng>(&self, pairings: &[(usize, usize)], rng: &mut R, results: &mut Results) {
+        let mut fixtures = vec![];
+        for &(first, second) in pairings {
+            for _ in 0..self.games_per_side {
+                fixtures.push(Fixture { home: first, away: second, seed: rng.gen() });
+                fixtures.push(Fixture { home: second, away: first, seed: rng.gen() });
+            }
+        }
+
+        let winners = run_parallel(fixtures.len() as u32, self.threads, |index| self.play(fixtures[index as usize]));
+        for (fixture, winner) in fixtures.iter().zip(winners) {
+            results.record(fixture, winner);
+        }
+    }
+
+    /// Plays a fixture, returning the winner if there was one, or `None` if the game was abandoned
+    fn play(&self, fixture: Fixture) -> Option<Option<usize>> {
+        let home = &self.entrants[fixture.home];
+        let away = &self.entrants[fixture.away];
+        let builder = PlayerBuilder::with_seed(fixture.seed);
+        let player1 = builder.new_player('x', home.kind.build_seeded(builder.next_seed())).expect("Symbols are distinct");
+        let player2 = builder.new_player('o', away.kind.build_seeded(builder.next_seed())).expect("Symbols are distinct");
+
+        let mut cycle = GameCycle::with_config(player1, player2, self.config);
+        cycle.set_display(false);
+        cycle.set_seed(fixture.seed);
+        match cycle.cycle_from(CurrentPlayer::PLAYER1) {
+            GameOutcome::Win { player, .. } if &player == cycle.player1() => Some(Some(fixture.home)),
+            GameOutcome::Win { .. } => Some(Some(fixture.away)),
+            GameOutcome::Draw => Some(None),
+            GameOutcome::Aborted { .. } => None,
+        }
+    }
+}
+
+/// The results so far, indexed by entrant
+struct Results {
+    head_to_head: Vec<Vec<Tally>>,
+    byes: Vec<u32>,
+    aborted: u32,
+}
+
+impl Results {
+    fn new(count: usize) -> Self {
+        Self {
+            head_to_head: vec![vec![Tally::default(); count]; count],
+            byes: vec![0; count],
+            aborted: 0,
+        }
+    }
+
+    fn record(&mut self, fixture: &Fixture, winner: Option<Option<usize>>) {
+        let (home, away) = (fixture.home, fixture.away);
+        match winner {
+            Some(Some(winner)) => {
+                let loser = if winner == home { away } else { home };
+                self.head_to_head[winner][loser].wins += 1;
+                self.head_to_head[loser][winner].losses += 1;
+            },
+            Some(None) => {
+                self.head_to_head[home][away].draws += 1;
+                self.head_to_head[away][home].draws += 1;
+            },
+            None => self.aborted += 1,
+        }
+    }
+
+    fn tally(&self, entrant: usize) -> Tally {
+        self.head_to_head[entrant].iter().fold(Tally::default(), |total, tally| Tally {
+            wins: total.wins + tally.wins,
+            draws: total.draws + tally.draws,
+            losses: total.losses + tally.losses,
+        })
+    }
+
+    /// Points in halves, so they can be compared exactly. A bye is worth winning every game of a
+    /// round.
+    fn half_points(&self, entrant: usize, games_per_round: u32) -> u32 {
+        let tally = self.tally(entrant);
+        2 * tally.wins + tally.draws + 2 * games_per_round * self.byes[entrant]
+    }
+
+    /// The entrants from best to worst: by points, then wins, then the order they were given in
+    fn ranking(&self, games_per_round: u32) -> Vec<usize> {
+        let mut ranking: Vec<usize> = (0..self.byes.len()).collect();
+        ranking.sort_by_key(|&entrant| {
+            (std::cmp::Reverse(self.half_points(entrant, games_per_round)), std::cmp::Reverse(self.tally(entrant).wins))
+        });
+        ranking
+    }
+
+    /// Pairs entrants with similar points for the next Swiss round. Each entrant is paired with
+    /// the best ranked entrant below it that it hasn't played yet, as long as everyone else can
+    /// still be paired without a rematch. If that is impossible, entrants are simply paired in
+    /// order. The lowest ranked entrant that has sat out the fewest rounds gets the bye.
+    fn swiss_pairings(&self) -> (Vec<(usize, usize)>, Option<usize>) {
+        // Byes are worth the same in every round, so any positive number of games ranks the same
+        let mut ranking = self.ranking(1);
+        let bye = if ranking.len() % 2 == 1 {
+            let fewest = self.byes.iter().copied().min().unwrap_or(0);
+            let position = ranking.iter()
+                .rposition(|&entrant| self.byes[entrant] == fewest)
+                .expect("Some entrant has the fewest byes");
+            Some(ranking.remove(position))
+        } else {
+            None
+        };
+
+        let pairings = self.pair_without_rematches(&ranking)
+            .unwrap_or_else(|| ranking.chunks(2).map(|pair| (pair[0], pair[1])).collect());
+        (pairings, bye)
+    }
+
+    fn pair_without_rematches(&self, unpaired: &[usize]) -> Option<Vec<(usize, usize)>> {
+        let (&first, rest) = match unpaired.split_first() {
+            Some(split) => split,
+            None => return Some(vec![])
+        };
+
+        rest.iter().enumerate()
+            .filter(|&(_, &other)| self.head_to_head[first][other].games() == 0)
+            .find_map(|(position, &other)| {
+                let mut remaining = rest.to_vec();
+                remaining.remove(position);
+                let mut pairings = self.pair_without_rematches(&remaining)?;
+                pairings.insert(0, (first, other));
+                Some(pairings)
+            })
+    }
+
+    fn into_standings(self, entrants: &[Entrant], games_per_round: u32) -> Standings {
+        let standings = self.ranking(games_per_round).into_iter()
+            .map(|entrant| Standing {
+                name: entrants[entrant].name.clone(),
+                tally: self.tally(entrant),
+                byes: self.byes[entrant],
+                points: self.half_points(entrant, games_per_round) as f64 / 2.0,
+            })
+            .collect();
+
+        Standings {
+            standings,
+            head_to_head: self.head_to_head,
+            names: entrants.iter().map(|entrant| entrant.name.clone()).collect(),
+            aborted: self.aborted,
+        }
+    }
+}
+
+impl Standings {
+    /// The total number of games played, including abandoned ones
+    pub fn games(&self) -> u32 {
+        // Every won game is counted once, by its winner, while every draw is counted by both entrants
+        let wins: u32 = self.standings.iter().map(|standing| standing.tally.wins).sum();
+        let draws: u32 = self.standings.iter().map(|standing| standing.tally.draws).sum();
+        wins + draws / 2 + self.aborted
+    }
+}
+
+impl Display for Standings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let width = self.names.iter().map(String::len).max().unwrap_or(0).max("Entrant".len());
+        let has_byes = self.standings.iter().any(|standing| standing.byes > 0);
+
+        write!(f, "{:>3}  {:<width$} {:>5} {:>5} {:>5}", "#", "Entrant", "W", "D", "L", width = width)?;
+        if has_byes {
+            write!(f, " {:>5}", "Byes")?;
+        }
+        writeln!(f, " {:>7}", "Points")?;
+        for (rank, standing) in self.standings.iter().enumerate() {
+            let tally = standing.tally;
+            write!(
+                f, "{:>3}  {:<width$} {:>5} {:>5} {:>5}",
+                rank + 1, standing.name, tally.wins, tally.draws, tally.losses, width = width
+            )?;
+            if has_byes {
+                write!(f, " {:>5}", standing.byes)?;
+            }
+            writeln!(f, " {:>7.1}", standing.points)?;
+        }
+        if self.aborted > 0 {
+            writeln!(f, "{} games were abandoned", self.aborted)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Head to head (wins-draws-losses of each row against each column):")?;
+        let cells: Vec<Vec<String>> = self.head_to_head.iter().enumerate()
+            .map(|(row, tallies)| tallies.iter().enumerate()
+                .map(|(column, tally)| match (row == column, tally.games()) {
+                    (true, _) => "x".to_string(),
+                    (false, 0) => "-".to_string(),
+                    (false, _) => format!("{}-{}-{}", tally.wins, tally.draws, tally.losses)
+                })
+                .collect())
+            .collect();
+        let cell_width = cells.iter().flatten().map(String::len).max().unwrap_or(1).max(3);
+
+        write!(f, "{:>3}  {:<width$}", "", "", width = width)?;
+        for column in 0..self.names.len() {
+            write!(f, " {:>cell_width$}", column + 1, cell_width = cell_width)?;
+        }
+        writeln!(f)?;
+        for (row, name) in self.names.iter().enumerate() {
+            write!(f, "{:>3}  {:<width$}", row + 1, name, width = width)?;
+            for cell in &cells[row] {
+                write!(f, " {:>cell_width$}", cell, cell_width = cell_width)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entrants(kinds: &[&str]) -> Vec<Entrant> {
+        kinds.iter().map(|kind| Entrant::new(kind.parse().unwrap())).collect()
+    }
+
+    #[test]
+    fn round_robins_play_every_pairing_both_ways() {
+        let mut tournament = Tournament::new(entrants(&["minimax", "random", "beginner"]), Format::RoundRobin).unwrap();
+        tournament.set_games_per_side(2);
+        tournament.set_threads(3);
+        tournament.set_seed(4);
+        let standings = tournament.run();
+
+        assert_eq!(standings.games(), 12);
+        assert_eq!(standings.standings[0].name, "minimax");
+        assert_eq!(standings.standings[0].tally.losses, 0);
+        for (row, tallies) in standings.head_to_head.iter().enumerate() {
+            for (column, tally) in tallies.iter().enumerate() {
+                assert_eq!(tally.games(), if row == column { 0 } else { 4 });
+                let reverse = standings.head_to_head[column][row];
+                assert_eq!((tally.wins, tally.draws), (reverse.losses, reverse.draws));
+            }
+        }
+    }
+
+    #[test]
+    fn seeded_tournaments_repeat() {
+        let run = |threads| {
+            let mut tournament = Tournament::new(entrants(&["random", "casual", "mcts:50"]), Format::RoundRobin).unwrap();
+            tournament.set_threads(threads);
+            tournament.set_seed(9);
+            tournament.run().to_string()
+        };
+        assert_eq!(run(1), run(4));
+    }
+
+    #[test]
+    fn swiss_rounds_avoid_rematches() {
+        let kinds = ["minimax", "random", "beginner", "casual", "hard"];
+        let mut tournament = Tournament::new(entrants(&kinds), Format::swiss_for(kinds.len())).unwrap();
+        assert_eq!(tournament.format, Format::Swiss { rounds: 3 });
+        tournament.set_seed(2);
+        let standings = tournament.run();
+
+        // 2 pairings a round, played both ways, with a different entrant sitting out each round
+        assert_eq!(standings.games(), 12);
+        let byes: Vec<u32> = standings.standings.iter().map(|standing| standing.byes).collect();
+        assert_eq!(byes.iter().sum::<u32>(), 3);
+        assert!(byes.iter().all(|&count| count <= 1));
+        for tallies in &standings.head_to_head {
+            assert!(tallies.iter().all(|tally| tally.games() <= 2), "No pairing should be played twice");
+        }
+    }
+
+    #[test]
+    fn invalid_entrants_are_rejected() {
+        assert_eq!(Tournament::new(entrants(&["random"]), Format::RoundRobin).err(), Some(TournamentError::TooFewEntrants));
+        assert_eq!(
+            Tournament::new(entrants(&["random", "Random"]), Format::RoundRobin).err(),
+            Some(TournamentError::DuplicateName("random".to_string()))
+        );
+    }
+}
@@ -1,22 +1,10 @@
-use crate::game::board::Board;
-use crate::game::player::{Player, PlayerBuilder};
-use crate::game::player::controllers::HumanController;
-use crate::game::cycle::GameCycle;
-
-pub mod game;
-
-#[macro_use]
-extern crate crossterm;
+mod cli;
 
 fn main() {
+    let matches = cli::app().get_matches();
 
-    let player_builder =PlayerBuilder::new();
-    let p1 = player_builder.new_player('x', HumanController.into()).unwrap();
-    let p2 = player_builder.new_player('o', HumanController.into()).unwrap();
-
-    let mut cycle = GameCycle::new(p1, p2);
-    let outcome = cycle.cycle();
-
-
+    if let Err(e) = cli::run(&matches) {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
 }
-